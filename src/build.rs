@@ -0,0 +1,127 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use crate::constants::GO_SOURCE_REPO;
+use crate::error::{GovmError, Result};
+
+/// Length of the abbreviated commit used in names like "tip-abc1234"
+const SHORT_COMMIT_LEN: usize = 7;
+
+/// The Gitiles repository to fetch source snapshots from, defaulting to go.googlesource.com
+pub fn repo(configured: Option<&str>) -> String {
+    configured
+        .filter(|r| !r.is_empty())
+        .unwrap_or(GO_SOURCE_REPO)
+        .trim_end_matches('/')
+        .to_string()
+}
+
+/// Extract the commit hash from a Gitiles `?format=JSON` response.
+///
+/// Gitiles prefixes JSON with `)]}'` to prevent XSSI; it is stripped here.
+pub fn parse_commit(body: &str) -> Option<String> {
+    let json = body.trim_start().trim_start_matches(")]}'");
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    let commit = value.get("commit")?.as_str()?;
+    is_commit(commit).then(|| commit.to_string())
+}
+
+/// Whether `reference` is a full 40-character commit hash
+pub fn is_commit(reference: &str) -> bool {
+    reference.len() == 40 && reference.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Version name for a build of `commit`, e.g. "tip-abc1234"
+pub fn tip_name(commit: &str) -> String {
+    format!("tip-{}", &commit[..SHORT_COMMIT_LEN.min(commit.len())])
+}
+
+/// Run `src/make.bash` in `goroot`, bootstrapping with the toolchain at `bootstrap`
+pub fn make(goroot: &Path, bootstrap: &Path) -> Result<()> {
+    let src_dir = goroot.join("src");
+    let script = src_dir.join("make.bash");
+    if !script.exists() {
+        return Err(GovmError::Build(format!("{} not found", script.display())));
+    }
+
+    let status = Command::new(&script)
+        .current_dir(&src_dir)
+        .env("GOROOT_BOOTSTRAP", bootstrap)
+        .env("GOTOOLCHAIN", "local")
+        .env_remove("GOROOT")
+        .status()
+        .map_err(|source| GovmError::Exec {
+            command: script.display().to_string(),
+            source,
+        })?;
+
+    if !status.success() {
+        return Err(GovmError::Build(format!(
+            "make.bash exited with {}",
+            status.code().unwrap_or(1)
+        )));
+    }
+    if !goroot.join("bin").join("go").exists() {
+        return Err(GovmError::Build(
+            "make.bash did not produce bin/go".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Write the VERSION file make.bash needs when building outside a git checkout
+pub fn write_devel_version(goroot: &Path, commit: &str) -> Result<()> {
+    let version_file = goroot.join("VERSION");
+    if !version_file.exists() {
+        fs::write(version_file, format!("devel {}\n", tip_name(commit)))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const COMMIT: &str = "abc1234def5678901234567890abcdef12345678";
+
+    #[test]
+    fn test_parse_commit_strips_xssi_prefix() {
+        let body = format!(")]}}'\n{{\"commit\": \"{}\", \"message\": \"x\"}}", COMMIT);
+        assert_eq!(parse_commit(&body), Some(COMMIT.to_string()));
+        assert_eq!(parse_commit(")]}'\n{\"commit\": \"nope\"}"), None);
+        assert_eq!(parse_commit("<html>"), None);
+    }
+
+    #[test]
+    fn test_tip_name() {
+        assert_eq!(tip_name(COMMIT), "tip-abc1234");
+        assert!(is_commit(COMMIT));
+        assert!(!is_commit("master"));
+    }
+
+    #[test]
+    fn test_write_devel_version_keeps_existing() {
+        let temp_dir = TempDir::new().unwrap();
+        write_devel_version(temp_dir.path(), COMMIT).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("VERSION")).unwrap(),
+            "devel tip-abc1234\n"
+        );
+
+        fs::write(temp_dir.path().join("VERSION"), "go1.22.0\n").unwrap();
+        write_devel_version(temp_dir.path(), COMMIT).unwrap();
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("VERSION")).unwrap(),
+            "go1.22.0\n"
+        );
+    }
+
+    #[test]
+    fn test_make_without_script_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let err = make(temp_dir.path(), temp_dir.path()).unwrap_err();
+        assert!(matches!(err, GovmError::Build(_)));
+    }
+}
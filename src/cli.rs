@@ -1,4 +1,8 @@
 use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use govm::migrate::Tool;
 
 #[derive(Parser)]
 #[command(name = "govm")]
@@ -12,12 +16,29 @@ pub struct Cli {
 
 #[derive(Subcommand)]
 pub enum Commands {
-    /// Install a specific Go version
+    /// Install a specific Go version, or the one this project requires
     #[command(alias = "i")]
     Install {
-        /// The Go version to install (e.g., 1.21.0, 1.22.0)
+        /// The Go versions to install (e.g., 1.21.0 1.22), downloaded in parallel.
+        /// Without one, the version from .go-version, govm.lock or go.mod is installed
         #[arg(name = "VERSION")]
-        go_version: String,
+        go_versions: Vec<String>,
+
+        /// Install the build for this OS instead of the current one (e.g., linux, darwin)
+        #[arg(long, requires = "VERSION")]
+        os: Option<String>,
+
+        /// Install the build for this architecture instead of the current one (e.g., arm64)
+        #[arg(long, requires = "VERSION")]
+        arch: Option<String>,
+
+        /// Build from source; VERSION may also be a commit or branch (e.g., master)
+        #[arg(long, conflicts_with_all = ["os", "arch"], requires = "VERSION")]
+        source: bool,
+
+        /// Install every version required by projects under this directory, in parallel
+        #[arg(long, value_name = "DIR", conflicts_with_all = ["VERSION", "os", "arch", "source"])]
+        all_projects: Option<PathBuf>,
     },
 
     /// Switch to a specific Go version (installs if needed)
@@ -49,7 +70,14 @@ pub enum Commands {
 
     /// List installed Go versions
     #[command(alias = "ls")]
-    Versions,
+    Versions {
+        /// Show when each version was last used
+        #[arg(short, long)]
+        verbose: bool,
+    },
+
+    /// Show disk space used by installed versions, caches and temp files
+    Du,
 
     /// List available Go versions for download
     #[command(alias = "ls-remote")]
@@ -71,6 +99,53 @@ pub enum Commands {
         go_version: String,
     },
 
+    /// Check installed versions against the file hashes recorded at install time
+    Verify {
+        /// The Go version to check (default: all installed versions)
+        #[arg(name = "VERSION")]
+        go_version: Option<String>,
+    },
+
+    /// Download a version again and swap it in, keeping global/local settings
+    Reinstall {
+        /// The Go version to reinstall
+        #[arg(name = "VERSION")]
+        go_version: String,
+    },
+
+    /// Write govm.lock, pinning this project's Go version and archive checksums
+    Lock {
+        /// The Go version to lock (default: the project's current version)
+        #[arg(name = "VERSION")]
+        go_version: Option<String>,
+    },
+
+    /// Register an existing Go installation (e.g., /usr/local/go) as a version
+    Link {
+        /// Name to use for the version (e.g., system)
+        #[arg(name = "NAME")]
+        name: String,
+
+        /// The installation's GOROOT
+        #[arg(name = "PATH")]
+        path: PathBuf,
+    },
+
+    /// Import Go versions installed by goenv, gvm, asdf or g
+    Migrate {
+        /// The version manager to import from (goenv, gvm, asdf, g)
+        #[arg(long, value_parser = govm::migrate::parse_tool)]
+        from: Tool,
+
+        /// Link toolchains instead of moving them, so the old tool keeps working
+        #[arg(long)]
+        link: bool,
+
+        /// Look for project files to update under these directories (default: current directory)
+        #[arg(long, value_name = "DIR", num_args = 1..)]
+        scan: Vec<PathBuf>,
+    },
+
     /// Show path to the Go executable that will be used
     Which {
         /// The command to look up (default: go)
@@ -80,6 +155,10 @@ pub enum Commands {
 
     /// Execute a command with the resolved Go version
     Exec {
+        /// Run a variant installed with --os/--arch (e.g., linux/arm64)
+        #[arg(long, value_parser = govm::version::parse_platform)]
+        platform: Option<(String, String)>,
+
         /// The command to execute
         command: String,
         /// Arguments to pass to the command
@@ -92,8 +171,69 @@ pub enum Commands {
 
     /// Prune old/unused Go versions
     Prune {
-        /// Keep this many latest versions
-        #[arg(short, long, default_value = "3")]
-        keep: usize,
+        /// Keep this many latest versions (default: prune.keep, or 3)
+        #[arg(short, long)]
+        keep: Option<usize>,
+
+        /// Remove versions not used within this age instead (e.g. 90d, 12h, 2w)
+        #[arg(long, value_name = "AGE", value_parser = govm::usage::parse_age)]
+        unused_for: Option<Duration>,
+
+        /// Also keep versions pinned by projects found under these directories
+        #[arg(long, value_name = "DIR", num_args = 1..)]
+        scan: Vec<PathBuf>,
+
+        /// Remove without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Show what would be removed without removing anything
+        #[arg(long)]
+        dry_run: bool,
     },
+
+    /// Manage Go tools installed alongside each version
+    Tools {
+        #[command(subcommand)]
+        command: ToolsCommand,
+    },
+
+    /// Show or change settings in ~/.govm/config.toml
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ToolsCommand {
+    /// Install the tools pinned by this project (.govm-tools or go.mod tool directives)
+    Install,
+
+    /// List the tools pinned by this project
+    #[command(alias = "ls")]
+    List,
+
+    /// Install the tools listed in ~/.govm/default-tools for a version
+    Reinstall {
+        /// The Go version to install the tools with
+        #[arg(name = "VERSION")]
+        go_version: String,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigCommand {
+    /// Print the value of a setting (e.g. prune.keep)
+    Get { key: String },
+
+    /// Store a setting
+    Set { key: String, value: String },
+
+    /// Remove a setting, restoring its default
+    Unset { key: String },
+
+    /// Show all settings
+    #[command(alias = "ls")]
+    List,
 }
@@ -0,0 +1,457 @@
+use colored::*;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::{Table, Value};
+
+use crate::constants::{
+    CA_BUNDLE_ENV, CONNECT_TIMEOUT_ENV, DOWNLOAD_BASE_ENV, ISOLATE_ENV, NO_SUPPORT_WARNINGS_ENV,
+    READ_TIMEOUT_ENV, SOURCE_REPO_ENV, STREAM_INSTALL_ENV, VERSION_LIST_ENV,
+};
+use crate::error::{GovmError, Result};
+use crate::usage;
+
+/// Settings that an environment variable overrides: (config key, variable)
+pub const ENV_OVERRIDES: &[(&str, &str)] = &[
+    ("mirror.download_base", DOWNLOAD_BASE_ENV),
+    ("mirror.version_list", VERSION_LIST_ENV),
+    ("mirror.source_repo", SOURCE_REPO_ENV),
+    ("network.connect_timeout", CONNECT_TIMEOUT_ENV),
+    ("network.read_timeout", READ_TIMEOUT_ENV),
+    ("network.ca_bundle", CA_BUNDLE_ENV),
+    ("install.isolate", ISOLATE_ENV),
+    ("install.stream", STREAM_INSTALL_ENV),
+    // Any non-empty value turns warnings off
+    ("warnings.support", NO_SUPPORT_WARNINGS_ENV),
+];
+
+/// govm settings from ~/.govm/config.toml.
+///
+/// Environment variables override the file, and command-line flags override both.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub mirror: MirrorConfig,
+    pub network: NetworkConfig,
+    pub install: InstallConfig,
+    pub resolve: ResolveConfig,
+    pub prune: PruneConfig,
+    pub cache: CacheConfig,
+    pub warnings: WarningsConfig,
+    pub ui: UiConfig,
+    /// Keys set by their environment variable rather than config.toml
+    #[serde(skip)]
+    pub from_env: Vec<&'static str>,
+}
+
+/// Where versions, archives and source snapshots come from
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MirrorConfig {
+    /// Mirror URL or local directory (GOVM_DOWNLOAD_BASE)
+    pub download_base: Option<String>,
+    /// Version index URL (GOVM_VERSION_LIST)
+    pub version_list: Option<String>,
+    /// Gitiles repository for `install --source` (GOVM_SOURCE_REPO)
+    pub source_repo: Option<String>,
+}
+
+/// HTTP client settings
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct NetworkConfig {
+    /// Seconds to wait for a connection
+    pub connect_timeout: Option<u64>,
+    /// Seconds to wait for data
+    pub read_timeout: Option<u64>,
+    /// Extra PEM bundle to trust
+    pub ca_bundle: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct InstallConfig {
+    /// Let `use` install versions that are missing
+    pub auto_install: bool,
+    /// Make the first installed version the global one
+    pub set_global_on_first_install: bool,
+    /// Per-version workspaces: "gobin" or "gopath" (GOVM_ISOLATE)
+    pub isolate: Option<String>,
+    /// Unpack archives as they download instead of saving them first (GOVM_STREAM_INSTALL)
+    pub stream: bool,
+}
+
+impl Default for InstallConfig {
+    fn default() -> Self {
+        Self {
+            auto_install: true,
+            set_global_on_first_install: true,
+            isolate: None,
+            stream: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResolveConfig {
+    /// Fall back to the nearest go.mod's toolchain/go directive when there is no .go-version
+    pub go_mod: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PruneConfig {
+    /// Default for `prune --keep`
+    pub keep: usize,
+    /// Default for `prune --unused-for`, e.g. "90d"
+    pub unused_for: Option<String>,
+}
+
+impl Default for PruneConfig {
+    fn default() -> Self {
+        Self {
+            keep: 3,
+            unused_for: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CacheConfig {
+    /// Reuse the cached version index for this long (e.g. "1h") instead of fetching it
+    pub index_ttl: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct WarningsConfig {
+    /// Warn about end-of-life and outdated versions (off with GOVM_NO_SUPPORT_WARNINGS)
+    pub support: bool,
+}
+
+impl Default for WarningsConfig {
+    fn default() -> Self {
+        Self { support: true }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct UiConfig {
+    /// Force colors on or off (default: auto, honoring NO_COLOR)
+    pub color: Option<bool>,
+}
+
+impl Config {
+    /// Load the config file (if any) and apply environment overrides
+    pub fn load(path: &Path) -> Result<Self> {
+        let table = read_table(path)?;
+        // Report problems in the file itself, not in the overrides
+        from_table(table.clone(), path)?;
+        let (table, from_env) = with_env(table);
+        Ok(Config {
+            from_env,
+            ..from_table(table, path)?
+        })
+    }
+
+    /// Check values that are typed as strings
+    fn validate(&self) -> Result<()> {
+        for (key, age) in [
+            ("prune.unused_for", &self.prune.unused_for),
+            ("cache.index_ttl", &self.cache.index_ttl),
+        ] {
+            if let Some(age) = age {
+                usage::parse_age(age).map_err(|e| GovmError::Config(format!("{}: {}", key, e)))?;
+            }
+        }
+        if let Some(mode) = &self.install.isolate {
+            if !["off", "gobin", "gopath"].contains(&mode.as_str()) {
+                return Err(GovmError::Config(format!(
+                    "install.isolate: '{}' is not one of off, gobin, gopath",
+                    mode
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Only the environment overrides, for callers without a govm root
+    pub fn from_env() -> Self {
+        let (table, from_env) = with_env(Table::new());
+        let config: Config = table.try_into().unwrap_or_default();
+        Config { from_env, ..config }
+    }
+}
+
+/// Apply environment overrides and return the keys they set. Values that
+/// don't fit their key are ignored with a warning.
+fn with_env(mut table: Table) -> (Table, Vec<&'static str>) {
+    let mut applied = Vec::new();
+    for (key, var) in ENV_OVERRIDES {
+        if let Some(value) = env_value(key, var) {
+            if let Err(e) = check_value(key, &value) {
+                eprintln!("{} Ignoring {}: {}", "⚠".yellow(), var, e);
+                continue;
+            }
+            set_key(&mut table, key, value);
+            applied.push(*key);
+        }
+    }
+    (table, applied)
+}
+
+/// The value of an overriding environment variable, typed for `key`
+fn env_value(key: &str, var: &str) -> Option<Value> {
+    let raw = env::var(var).ok().filter(|v| !v.is_empty())?;
+    if key == "warnings.support" {
+        return Some(Value::Boolean(false));
+    }
+    if key == "install.stream" {
+        return Some(Value::Boolean(!matches!(raw.trim(), "0" | "false")));
+    }
+    Some(parse_value(raw.trim()))
+}
+
+/// Parse a value given on the command line: booleans and integers, else a string
+pub fn parse_value(raw: &str) -> Value {
+    if let Ok(b) = raw.parse::<bool>() {
+        Value::Boolean(b)
+    } else if let Ok(i) = raw.parse::<i64>() {
+        Value::Integer(i)
+    } else {
+        Value::String(raw.to_string())
+    }
+}
+
+/// Read the config file as a TOML table; a missing file is empty
+pub fn read_table(path: &Path) -> Result<Table> {
+    match fs::read_to_string(path) {
+        Ok(content) => content
+            .parse::<Table>()
+            .map_err(|e| GovmError::Config(format!("{}: {}", path.display(), e))),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Table::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn from_table(table: Table, path: &Path) -> Result<Config> {
+    let config: Config = table
+        .try_into()
+        .map_err(|e: toml::de::Error| GovmError::Config(format!("{}: {}", path.display(), e)))?;
+    config.validate()?;
+    Ok(config)
+}
+
+/// Look up a dotted key like "prune.keep" in the effective config
+pub fn get(config: &Config, key: &str) -> Result<Option<Value>> {
+    let table = Table::try_from(config).map_err(|e| GovmError::Config(e.to_string()))?;
+    let (section, name) = split_key(key)?;
+    match table.get(section).and_then(Value::as_table) {
+        Some(section) => Ok(section.get(name).cloned()),
+        None => Err(GovmError::Config(format!("Unknown setting '{}'", key))),
+    }
+}
+
+/// Set a dotted key in the config file. Only the new value is validated, so
+/// a broken setting elsewhere in the file doesn't stop it from being fixed.
+pub fn set(path: &Path, key: &str, value: &str) -> Result<()> {
+    split_key(key)?;
+    let typed = parse_value(value);
+    let value = match check_value(key, &typed) {
+        Ok(()) => typed,
+        // e.g. `prune.unused_for 90` is the string "90"
+        Err(_) => {
+            let string = Value::String(value.to_string());
+            check_value(key, &string)?;
+            string
+        }
+    };
+    let mut table = read_table(path)?;
+    set_key(&mut table, key, value);
+    write_table(path, table)
+}
+
+/// Check the type (and for strings, the format) of a single setting
+fn check_value(key: &str, value: &Value) -> Result<()> {
+    let mut table = Table::new();
+    set_key(&mut table, key, value.clone());
+    let config: Config = table
+        .try_into()
+        .map_err(|e: toml::de::Error| GovmError::Config(format!("{}: {}", key, e.message())))?;
+    config.validate()
+}
+
+/// Remove a dotted key from the config file
+pub fn unset(path: &Path, key: &str) -> Result<()> {
+    let mut table = read_table(path)?;
+    let (section, name) = split_key(key)?;
+    if let Some(Value::Table(section_table)) = table.get_mut(section) {
+        section_table.remove(name);
+        if section_table.is_empty() {
+            table.remove(section);
+        }
+    }
+    write_table(path, table)
+}
+
+/// All settings as (key, value) pairs; unset optional settings have no value
+pub fn list(config: &Config) -> Result<Vec<(String, Option<Value>)>> {
+    let table = Table::try_from(config).map_err(|e| GovmError::Config(e.to_string()))?;
+    let mut settings = Vec::new();
+    for (section, keys) in KEYS {
+        for name in *keys {
+            let value = table.get(*section).and_then(|s| s.get(*name)).cloned();
+            settings.push((format!("{}.{}", section, name), value));
+        }
+    }
+    Ok(settings)
+}
+
+/// Every setting, so optional ones that are unset still show up in `config list`
+const KEYS: &[(&str, &[&str])] = &[
+    ("mirror", &["download_base", "version_list", "source_repo"]),
+    ("network", &["connect_timeout", "read_timeout", "ca_bundle"]),
+    (
+        "install",
+        &[
+            "auto_install",
+            "set_global_on_first_install",
+            "isolate",
+            "stream",
+        ],
+    ),
+    ("resolve", &["go_mod"]),
+    ("prune", &["keep", "unused_for"]),
+    ("cache", &["index_ttl"]),
+    ("warnings", &["support"]),
+    ("ui", &["color"]),
+];
+
+fn split_key(key: &str) -> Result<(&str, &str)> {
+    key.split_once('.')
+        .filter(|(section, name)| {
+            KEYS.iter()
+                .any(|(s, names)| s == section && names.contains(name))
+        })
+        .ok_or_else(|| GovmError::Config(format!("Unknown setting '{}'", key)))
+}
+
+fn set_key(table: &mut Table, key: &str, value: Value) {
+    let Some((section, name)) = key.split_once('.') else {
+        return;
+    };
+    let section = table
+        .entry(section)
+        .or_insert_with(|| Value::Table(Table::new()));
+    if let Value::Table(section) = section {
+        section.insert(name.to_string(), value);
+    }
+}
+
+fn write_table(path: &Path, table: Table) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let content = toml::to_string_pretty(&table).map_err(|e| GovmError::Config(e.to_string()))?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_defaults_without_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let table = read_table(&temp_dir.path().join("config.toml")).unwrap();
+        let config = from_table(table, temp_dir.path()).unwrap();
+        assert_eq!(config, Config::default());
+        assert_eq!(config.prune.keep, 3);
+        assert!(config.install.auto_install);
+        assert!(config.warnings.support);
+    }
+
+    #[test]
+    fn test_set_get_unset() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+
+        set(&path, "prune.keep", "5").unwrap();
+        set(
+            &path,
+            "mirror.download_base",
+            "https://mirror.example.com/go/",
+        )
+        .unwrap();
+        let config = from_table(read_table(&path).unwrap(), &path).unwrap();
+        assert_eq!(config.prune.keep, 5);
+        assert_eq!(
+            get(&config, "mirror.download_base").unwrap(),
+            Some(Value::String("https://mirror.example.com/go/".to_string()))
+        );
+
+        // Strings that look like numbers stay strings
+        set(&path, "prune.unused_for", "90").unwrap();
+        let config = from_table(read_table(&path).unwrap(), &path).unwrap();
+        assert_eq!(config.prune.unused_for.as_deref(), Some("90"));
+
+        unset(&path, "prune.keep").unwrap();
+        let config = from_table(read_table(&path).unwrap(), &path).unwrap();
+        assert_eq!(config.prune.keep, 3);
+    }
+
+    #[test]
+    fn test_set_rejects_unknown_keys_and_bad_types() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+
+        assert!(set(&path, "prune.kep", "5").is_err());
+        assert!(set(&path, "prune.keep", "lots").is_err());
+        assert!(set(&path, "install.isolate", "everything").is_err());
+        assert!(set(&path, "cache.index_ttl", "soon").is_err());
+        assert!(!path.exists(), "Invalid settings are never written");
+    }
+
+    #[test]
+    fn test_set_and_unset_repair_invalid_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[install]\nisolate = 3\n\n[prune]\nkeep = \"x\"\n").unwrap();
+
+        set(&path, "prune.keep", "2").unwrap();
+        unset(&path, "install.isolate").unwrap();
+        let config = from_table(read_table(&path).unwrap(), &path).unwrap();
+        assert_eq!(config.prune.keep, 2);
+        assert_eq!(config.install.isolate, None);
+    }
+
+    #[test]
+    fn test_invalid_file_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("config.toml");
+        fs::write(&path, "[prune]\nkeep = \"three\"\n").unwrap();
+        let table = read_table(&path).unwrap();
+        assert!(matches!(
+            from_table(table, &path),
+            Err(GovmError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_list_includes_unset_settings() {
+        let settings = list(&Config::default()).unwrap();
+        let keys: Vec<_> = settings.iter().map(|(k, _)| k.as_str()).collect();
+        assert!(keys.contains(&"mirror.download_base"));
+        assert!(keys.contains(&"prune.keep"));
+        assert_eq!(
+            settings.iter().find(|(k, _)| k == "prune.keep").unwrap().1,
+            Some(Value::Integer(3))
+        );
+    }
+}
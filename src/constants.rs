@@ -4,5 +4,44 @@ pub const GO_DOWNLOAD_BASE: &str = "https://go.dev/dl/";
 /// API endpoint for Go version list
 pub const GO_VERSION_LIST: &str = "https://go.dev/dl/?mode=json&include=all";
 
+/// Git repository Go source snapshots are fetched from (Gitiles)
+pub const GO_SOURCE_REPO: &str = "https://go.googlesource.com/go";
+
+/// User agent sent with every request
+pub const USER_AGENT: &str = concat!("govm/", env!("CARGO_PKG_VERSION"));
+
 /// List of Go binaries that need shims
 pub const GO_BINARIES: &[&str] = &["go", "gofmt"];
+
+/// Environment variable that silences end-of-life and outdated-patch warnings
+pub const NO_SUPPORT_WARNINGS_ENV: &str = "GOVM_NO_SUPPORT_WARNINGS";
+
+/// Environment variable selecting a download mirror URL or local directory
+pub const DOWNLOAD_BASE_ENV: &str = "GOVM_DOWNLOAD_BASE";
+
+/// Environment variable overriding the version list URL
+pub const VERSION_LIST_ENV: &str = "GOVM_VERSION_LIST";
+
+/// Environment variable pointing at an extra PEM CA bundle to trust
+pub const CA_BUNDLE_ENV: &str = "GOVM_CA_BUNDLE";
+
+/// Environment variable overriding the connect timeout (seconds)
+pub const CONNECT_TIMEOUT_ENV: &str = "GOVM_CONNECT_TIMEOUT";
+
+/// Environment variable overriding the read timeout (seconds)
+pub const READ_TIMEOUT_ENV: &str = "GOVM_READ_TIMEOUT";
+
+/// Environment variable holding a bearer token for the configured mirror
+pub const MIRROR_TOKEN_ENV: &str = "GOVM_MIRROR_TOKEN";
+
+/// Environment variable selecting the platform variant `exec` runs, e.g. "linux/arm64"
+pub const PLATFORM_ENV: &str = "GOVM_PLATFORM";
+
+/// Environment variable overriding the Gitiles repository used by `install --source`
+pub const SOURCE_REPO_ENV: &str = "GOVM_SOURCE_REPO";
+
+/// Environment variable enabling per-version workspaces: "gobin" or "gopath"
+pub const ISOLATE_ENV: &str = "GOVM_ISOLATE";
+
+/// Environment variable making installs unpack archives while they download
+pub const STREAM_INSTALL_ENV: &str = "GOVM_STREAM_INSTALL";
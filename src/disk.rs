@@ -0,0 +1,42 @@
+use std::fs;
+use std::path::Path;
+
+/// Total size in bytes of all files under a path.
+///
+/// Symlinks are counted as links, not followed. Unreadable entries are skipped.
+pub fn dir_size(path: &Path) -> u64 {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+
+    let Ok(entries) = fs::read_dir(path) else {
+        return 0;
+    };
+    entries.flatten().map(|entry| dir_size(&entry.path())).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_dir_size_sums_nested_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("bin");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("VERSION"), "go1.22.0").unwrap();
+        fs::write(nested.join("go"), vec![0u8; 1000]).unwrap();
+
+        assert_eq!(dir_size(temp_dir.path()), 1008);
+    }
+
+    #[test]
+    fn test_dir_size_missing_path() {
+        let temp_dir = TempDir::new().unwrap();
+        assert_eq!(dir_size(&temp_dir.path().join("missing")), 0);
+    }
+}
@@ -1,49 +1,115 @@
-use anyhow::Result;
 use flate2::read::GzDecoder;
 use futures_util::StreamExt;
-use indicatif::{ProgressBar, ProgressStyle};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use sha2::{Digest, Sha256};
 use std::fs::{self, File};
-use std::io::Write;
-use std::path::PathBuf;
+use std::future::Future;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
 use tar::Archive;
+use tokio::sync::mpsc;
 
+use crate::auth::{self, Credentials};
 use crate::constants::GO_VERSION_LIST;
+use crate::error::{GovmError, Result};
+use crate::http;
 use crate::types::GoVersion;
 
 /// Fetch list of available Go versions from the official API
 pub async fn fetch_remote_versions() -> Result<Vec<GoVersion>> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(GO_VERSION_LIST)
-        .header("User-Agent", "govm/0.1.0")
-        .send()
-        .await?
-        .json::<Vec<GoVersion>>()
-        .await?;
+    fetch_versions_from(GO_VERSION_LIST, None).await
+}
+
+/// Fetch list of available Go versions from a go.dev-compatible index URL
+pub async fn fetch_versions_from(
+    url: &str,
+    credentials: Option<&Credentials>,
+) -> Result<Vec<GoVersion>> {
+    let response = with_timeout(url, get(url, credentials)?.send()).await??;
+    let response =
+        with_timeout(url, check_status(url, response)?.json::<Vec<GoVersion>>()).await??;
     Ok(response)
 }
 
-/// Download a file with progress bar
-pub async fn download_file(url: &str, path: &PathBuf, total_size: u64) -> Result<()> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(url)
-        .header("User-Agent", "govm/0.1.0")
-        .send()
-        .await?;
-
-    let pb = ProgressBar::new(total_size);
+/// Fetch a URL as text
+pub async fn fetch_text(url: &str, credentials: Option<&Credentials>) -> Result<String> {
+    let response = with_timeout(url, get(url, credentials)?.send()).await??;
+    let text = with_timeout(url, check_status(url, response)?.text()).await??;
+    Ok(text)
+}
+
+/// Build a GET request on the shared client, authenticated if credentials are given
+fn get(url: &str, credentials: Option<&Credentials>) -> Result<reqwest::RequestBuilder> {
+    let request = http::client()?.get(url);
+    Ok(match credentials {
+        Some(credentials) => credentials.apply(request),
+        None => request,
+    })
+}
+
+/// Fail with `GovmError::Timeout` if no data arrives within the read timeout
+async fn with_timeout<F: Future>(url: &str, future: F) -> Result<F::Output> {
+    tokio::time::timeout(http::config().read_timeout, future)
+        .await
+        .map_err(|_| GovmError::Timeout {
+            url: auth::redact(url),
+        })
+}
+
+/// Turn an HTTP error status into a `GovmError`
+fn check_status(url: &str, response: reqwest::Response) -> Result<reqwest::Response> {
+    let status = response.status();
+    if !status.is_success() {
+        return Err(GovmError::HttpStatus {
+            url: auth::redact(url),
+            status: status.as_u16(),
+        });
+    }
+    Ok(response)
+}
+
+/// Progress bars of all downloads in this process, so parallel downloads
+/// each get their own line instead of overwriting one another
+fn progress() -> &'static MultiProgress {
+    static PROGRESS: OnceLock<MultiProgress> = OnceLock::new();
+    PROGRESS.get_or_init(MultiProgress::new)
+}
+
+/// Run `f` with the download progress bars hidden, so what it prints doesn't
+/// garble them. Keep `f` short: bars of parallel downloads can't move meanwhile.
+pub fn suspend_progress<R>(f: impl FnOnce() -> R) -> R {
+    progress().suspend(f)
+}
+
+/// A download progress bar for `total_size` bytes
+fn progress_bar(total_size: u64) -> ProgressBar {
+    let pb = progress().add(ProgressBar::new(total_size));
     pb.set_style(
         ProgressStyle::default_bar()
-            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")?
+            .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+            .expect("valid progress bar template")
             .progress_chars("█▓▒░"),
     );
+    pb
+}
+
+/// Download a file with progress bar
+pub async fn download_file(
+    url: &str,
+    path: &PathBuf,
+    total_size: u64,
+    credentials: Option<&Credentials>,
+) -> Result<()> {
+    let response = with_timeout(url, get(url, credentials)?.send()).await??;
+    let response = check_status(url, response)?;
 
+    let pb = progress_bar(total_size);
     let mut file = File::create(path)?;
     let mut downloaded: u64 = 0;
     let mut stream = response.bytes_stream();
 
-    while let Some(chunk) = stream.next().await {
+    while let Some(chunk) = with_timeout(url, stream.next()).await? {
         let chunk = chunk?;
         file.write_all(&chunk)?;
         downloaded += chunk.len() as u64;
@@ -54,8 +120,131 @@ pub async fn download_file(url: &str, path: &PathBuf, total_size: u64) -> Result
     Ok(())
 }
 
+/// Download a .tar.gz archive and unpack it into `dir` as it arrives, without
+/// writing the archive to disk. Returns the archive's sha256.
+///
+/// Whatever was unpacked must not be used until the checksum is checked.
+pub async fn download_unpacked(
+    url: &str,
+    dir: &Path,
+    total_size: u64,
+    credentials: Option<&Credentials>,
+) -> Result<String> {
+    let response = with_timeout(url, get(url, credentials)?.send()).await??;
+    let response = check_status(url, response)?;
+
+    // tar is blocking, so it reads the chunks on a blocking thread
+    let (sender, receiver) = mpsc::channel(16);
+    let dir = dir.to_path_buf();
+    let unpack =
+        tokio::task::spawn_blocking(move || unpack_hashed(ChunkReader::new(receiver), &dir));
+
+    let pb = progress_bar(total_size);
+    let mut downloaded: u64 = 0;
+    let mut stream = response.bytes_stream();
+
+    let download = async {
+        while let Some(chunk) = with_timeout(url, stream.next()).await? {
+            let chunk = chunk?;
+            downloaded += chunk.len() as u64;
+            pb.set_position(downloaded);
+            if sender.send(chunk).await.is_err() {
+                // Unpacking failed; its error is returned below
+                break;
+            }
+        }
+        Ok::<_, GovmError>(())
+    }
+    .await;
+    // Unpacking must be over before the caller cleans up `dir`, even on errors
+    drop(sender);
+    let unpacked = unpack
+        .await
+        .map_err(|e| GovmError::Archive(e.to_string()))?;
+    // A cut-off download explains a truncated archive better than tar does
+    download?;
+
+    pb.finish_with_message("Download complete");
+    unpacked
+}
+
+/// Unpack a .tar.gz stream into `dir` and return the sha256 of all of it
+pub fn unpack_hashed(reader: impl Read, dir: &Path) -> Result<String> {
+    fs::create_dir_all(dir)?;
+    let mut archive = Archive::new(GzDecoder::new(HashingReader::new(reader)));
+    archive.unpack(dir)?;
+    // The checksum covers the whole archive, including what tar didn't need to read
+    let mut reader = archive.into_inner().into_inner();
+    io::copy(&mut reader, &mut io::sink())?;
+    Ok(reader.finish())
+}
+
+/// Passes reads through while hashing everything read
+struct HashingReader<R> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Hex sha256 of everything read so far
+    fn finish(self) -> String {
+        format!("{:x}", self.hasher.finalize())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Blocking reader over chunks sent from an async task; ends when the sender is dropped
+struct ChunkReader<T> {
+    receiver: mpsc::Receiver<T>,
+    chunk: T,
+    pos: usize,
+}
+
+impl<T: AsRef<[u8]> + Default> ChunkReader<T> {
+    fn new(receiver: mpsc::Receiver<T>) -> Self {
+        Self {
+            receiver,
+            chunk: T::default(),
+            pos: 0,
+        }
+    }
+}
+
+impl<T: AsRef<[u8]> + Default> Read for ChunkReader<T> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.as_ref().len() {
+            match self.receiver.blocking_recv() {
+                Some(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                None => return Ok(0),
+            }
+        }
+        let rest = &self.chunk.as_ref()[self.pos..];
+        let n = rest.len().min(buf.len());
+        buf[..n].copy_from_slice(&rest[..n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
 /// Extract a .tar.gz archive to a destination directory
-pub fn extract_archive(archive_path: &PathBuf, dest_dir: &PathBuf, temp_dir: &PathBuf) -> Result<()> {
+pub fn extract_archive(archive_path: &Path, dest_dir: &Path, temp_dir: &Path) -> Result<()> {
     let tar_gz = File::open(archive_path)?;
     let tar = GzDecoder::new(tar_gz);
     let mut archive = Archive::new(tar);
@@ -65,11 +254,21 @@ pub fn extract_archive(archive_path: &PathBuf, dest_dir: &PathBuf, temp_dir: &Pa
 
     archive.unpack(temp_dir)?;
 
-    // Move the 'go' directory to the version directory
+    move_goroot(temp_dir, dest_dir, &archive_path.display().to_string())
+}
+
+/// Move the top-level 'go' directory unpacked from `archive` into `dest_dir`,
+/// then remove `temp_dir`
+pub fn move_goroot(temp_dir: &Path, dest_dir: &Path, archive: &str) -> Result<()> {
     let extracted_go = temp_dir.join("go");
-    if extracted_go.exists() {
-        fs::rename(&extracted_go, dest_dir)?;
+    if !extracted_go.exists() {
+        let _ = fs::remove_dir_all(temp_dir);
+        return Err(GovmError::Archive(format!(
+            "{} has no top-level 'go' directory",
+            archive
+        )));
     }
+    fs::rename(&extracted_go, dest_dir)?;
 
     // Clean up temp directory
     let _ = fs::remove_dir_all(temp_dir);
@@ -77,7 +276,18 @@ pub fn extract_archive(archive_path: &PathBuf, dest_dir: &PathBuf, temp_dir: &Pa
     Ok(())
 }
 
-/// Get the current platform (os, arch)
+/// Extract a .tar.gz archive that has no top-level directory straight into `dest_dir`
+pub fn extract_archive_flat(archive_path: &PathBuf, dest_dir: &PathBuf) -> Result<()> {
+    let mut archive = Archive::new(GzDecoder::new(File::open(archive_path)?));
+    fs::create_dir_all(dest_dir)?;
+    if let Err(e) = archive.unpack(dest_dir) {
+        let _ = fs::remove_dir_all(dest_dir);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Get the current platform (os, arch) using the names Go uses in its downloads
 pub fn get_platform() -> (&'static str, &'static str) {
     let os = if cfg!(target_os = "macos") {
         "darwin"
@@ -85,6 +295,20 @@ pub fn get_platform() -> (&'static str, &'static str) {
         "linux"
     } else if cfg!(target_os = "windows") {
         "windows"
+    } else if cfg!(target_os = "freebsd") {
+        "freebsd"
+    } else if cfg!(target_os = "openbsd") {
+        "openbsd"
+    } else if cfg!(target_os = "netbsd") {
+        "netbsd"
+    } else if cfg!(target_os = "dragonfly") {
+        "dragonfly"
+    } else if cfg!(target_os = "illumos") {
+        "illumos"
+    } else if cfg!(target_os = "solaris") {
+        "solaris"
+    } else if cfg!(target_os = "aix") {
+        "aix"
     } else {
         "unknown"
     };
@@ -95,6 +319,27 @@ pub fn get_platform() -> (&'static str, &'static str) {
         "arm64"
     } else if cfg!(target_arch = "x86") {
         "386"
+    } else if cfg!(target_arch = "arm") {
+        // Go ships a single 32-bit ARM build that runs on ARMv6 and later
+        "armv6l"
+    } else if cfg!(all(target_arch = "powerpc64", target_endian = "little")) {
+        "ppc64le"
+    } else if cfg!(target_arch = "powerpc64") {
+        "ppc64"
+    } else if cfg!(target_arch = "s390x") {
+        "s390x"
+    } else if cfg!(target_arch = "riscv64") {
+        "riscv64"
+    } else if cfg!(target_arch = "loongarch64") {
+        "loong64"
+    } else if cfg!(all(target_arch = "mips64", target_endian = "little")) {
+        "mips64le"
+    } else if cfg!(target_arch = "mips64") {
+        "mips64"
+    } else if cfg!(all(target_arch = "mips", target_endian = "little")) {
+        "mipsle"
+    } else if cfg!(target_arch = "mips") {
+        "mips"
     } else {
         "unknown"
     };
@@ -105,13 +350,52 @@ pub fn get_platform() -> (&'static str, &'static str) {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_unpack_hashed_covers_whole_archive() {
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "go/VERSION", &b"go1.0"[..])
+            .unwrap();
+        let mut archive = builder.into_inner().unwrap().finish().unwrap();
+        // Bytes after the gzip stream are never read by tar, but still count
+        archive.extend_from_slice(b"trailer");
+
+        let temp_dir = TempDir::new().unwrap();
+        let checksum = unpack_hashed(&archive[..], temp_dir.path()).unwrap();
+        assert_eq!(checksum, format!("{:x}", Sha256::digest(&archive)));
+        assert_eq!(
+            fs::read_to_string(temp_dir.path().join("go/VERSION")).unwrap(),
+            "go1.0"
+        );
+    }
 
     #[test]
     fn test_get_platform_returns_valid_os() {
         let (os, _) = get_platform();
         assert!(
-            ["darwin", "linux", "windows", "unknown"].contains(&os),
-            "OS should be one of darwin, linux, windows, or unknown"
+            [
+                "darwin",
+                "linux",
+                "windows",
+                "freebsd",
+                "openbsd",
+                "netbsd",
+                "dragonfly",
+                "illumos",
+                "solaris",
+                "aix",
+                "unknown"
+            ]
+            .contains(&os),
+            "OS should be one Go publishes binaries for, or unknown"
         );
     }
 
@@ -119,8 +403,12 @@ mod tests {
     fn test_get_platform_returns_valid_arch() {
         let (_, arch) = get_platform();
         assert!(
-            ["amd64", "arm64", "386", "unknown"].contains(&arch),
-            "Arch should be one of amd64, arm64, 386, or unknown"
+            [
+                "amd64", "arm64", "386", "armv6l", "ppc64le", "ppc64", "s390x", "riscv64",
+                "loong64", "mips64le", "mips64", "mipsle", "mips", "unknown"
+            ]
+            .contains(&arch),
+            "Arch should be one Go publishes binaries for, or unknown"
         );
     }
 
@@ -0,0 +1,227 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors returned by the govm library
+#[derive(Debug, Error)]
+pub enum GovmError {
+    /// The requested version does not exist upstream
+    #[error("Version {0} not found")]
+    VersionNotFound(String),
+
+    /// The version exists, but not for this OS/architecture
+    #[error(
+        "No binary available for {os} {arch} (Go {version}). Available platforms: {}",
+        .available.join(", ")
+    )]
+    NoBinaryForPlatform {
+        version: String,
+        os: String,
+        arch: String,
+        /// "os/arch" pairs that do have an archive for this version
+        available: Vec<String>,
+    },
+
+    /// The platform's archive is in a format govm can't unpack (the .zip for windows)
+    #[error("No .tar.gz archive for {os} {arch} (Go {version}), only {filename}")]
+    UnsupportedArchive {
+        version: String,
+        os: String,
+        arch: String,
+        filename: String,
+    },
+
+    /// The version is not installed locally
+    #[error("Go {0} is not installed. Run 'govm install {0}' first.")]
+    NotInstalled(String),
+
+    /// A version with this name already exists
+    #[error("Go {0} is already installed. Run 'govm uninstall {0}' first.")]
+    AlreadyInstalled(String),
+
+    /// A version name that can't be used as a directory name
+    #[error("Invalid version name '{0}'")]
+    InvalidVersionName(String),
+
+    /// The path given to `govm link` is not a Go installation
+    #[error("{} is not a Go installation (no bin/go)", .0.display())]
+    NotAGoroot(PathBuf),
+
+    /// Nothing selects a version for the current directory
+    #[error("No Go version configured. Run 'govm global <version>' or create a .go-version file")]
+    NoVersionConfigured,
+
+    /// `govm install` without a version, outside any project
+    #[error("No .go-version, govm.lock or go.mod found. Run 'govm install <version>'")]
+    NoProjectVersion,
+
+    /// The installed version has no such binary
+    #[error("Command '{command}' not found in Go {version}")]
+    CommandNotFound { command: String, version: String },
+
+    /// The Go binary could not be started
+    #[error("Failed to execute {command}: {source}")]
+    Exec {
+        command: String,
+        #[source]
+        source: io::Error,
+    },
+
+    /// A platform like "linux/arm64" could not be parsed
+    #[error("{0}")]
+    InvalidPlatform(String),
+
+    #[error("Could not find home directory")]
+    HomeNotFound,
+
+    /// An age like "90d" could not be parsed
+    #[error("{0}")]
+    InvalidAge(String),
+
+    /// A destructive operation needs confirmation, but stdin is not a terminal
+    #[error(
+        "Refusing to {0} without confirmation: stdin is not a terminal. Pass --yes to confirm."
+    )]
+    ConfirmationRequired(&'static str),
+
+    /// Request to the download server failed
+    #[error("Network error: {0}")]
+    Http(reqwest::Error),
+
+    /// The server stopped sending data
+    #[error("Timed out waiting for {url}")]
+    Timeout { url: String },
+
+    /// Download server answered with an error status
+    #[error("Download of {url} failed with HTTP {status}")]
+    HttpStatus { url: String, status: u16 },
+
+    /// The version index could not be parsed
+    #[error("Invalid version index: {0}")]
+    InvalidIndex(String),
+
+    /// Building from source needs an installed toolchain to bootstrap with
+    #[error("Building Go from source needs an installed Go to bootstrap. Run 'govm install <version>' first.")]
+    BootstrapRequired,
+
+    /// make.bash failed
+    #[error("Building Go from source failed: {0}")]
+    Build(String),
+
+    /// No .govm-tools file or go.mod tool directives found
+    #[error("No tools pinned. Create a .govm-tools file or add tool directives to go.mod")]
+    NoToolsPinned,
+
+    /// Some tools could not be installed
+    #[error("Failed to install: {}", .0.join(", "))]
+    ToolsFailed(Vec<String>),
+
+    /// config.toml is invalid, or a setting name or value is
+    #[error("Invalid config: {0}")]
+    Config(String),
+
+    /// A pre-hook exited non-zero or could not be started
+    #[error("Hook {hook} failed ({reason})")]
+    HookFailed { hook: String, reason: String },
+
+    /// Installed files no longer match the manifest recorded at install time
+    #[error("Verification failed for Go {}. Run 'govm reinstall <version>' to repair.", .0.join(", "))]
+    VerifyFailed(Vec<String>),
+
+    /// The version can't be downloaded again (linked or built from source)
+    #[error("Cannot reinstall {version}: {reason}")]
+    CannotReinstall { version: String, reason: String },
+
+    /// A downloaded archive doesn't have the published or locked checksum
+    #[error("Checksum mismatch for {file}: expected {expected}, got {actual}")]
+    ChecksumMismatch {
+        file: String,
+        expected: String,
+        actual: String,
+    },
+
+    /// govm.lock is invalid or the selected toolchain doesn't match it
+    #[error("{0}")]
+    Lock(String),
+
+    /// Some of several versions could not be installed
+    #[error("Failed to install Go {}", .0.join(", "))]
+    InstallsFailed(Vec<String>),
+
+    /// Archive could not be unpacked or has an unexpected layout
+    #[error("Invalid archive: {0}")]
+    Archive(String),
+
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+impl From<reqwest::Error> for GovmError {
+    fn from(err: reqwest::Error) -> Self {
+        // URLs may carry mirror credentials; never show them
+        let has_user_info = err
+            .url()
+            .is_some_and(|u| !u.username().is_empty() || u.password().is_some());
+        if has_user_info {
+            GovmError::Http(err.without_url())
+        } else {
+            GovmError::Http(err)
+        }
+    }
+}
+
+impl GovmError {
+    /// Process exit code the CLI uses for this error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            GovmError::VersionNotFound(_) => 3,
+            GovmError::NoBinaryForPlatform { .. } | GovmError::UnsupportedArchive { .. } => 4,
+            GovmError::Http(_) | GovmError::HttpStatus { .. } | GovmError::Timeout { .. } => 5,
+            GovmError::NotInstalled(_) => 6,
+            GovmError::NoVersionConfigured | GovmError::NoProjectVersion => 7,
+            GovmError::CommandNotFound { .. } => 127,
+            _ => 1,
+        }
+    }
+}
+
+/// Result type used throughout the govm library
+pub type Result<T> = std::result::Result<T, GovmError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_are_distinct() {
+        let errors = [
+            GovmError::VersionNotFound("1.99.0".to_string()),
+            GovmError::NoBinaryForPlatform {
+                version: "1.22.0".to_string(),
+                os: "plan9".to_string(),
+                arch: "arm".to_string(),
+                available: vec!["linux/amd64".to_string()],
+            },
+            GovmError::HttpStatus {
+                url: "https://go.dev/dl/".to_string(),
+                status: 503,
+            },
+            GovmError::NotInstalled("1.22.0".to_string()),
+            GovmError::NoVersionConfigured,
+        ];
+        let mut codes: Vec<i32> = errors.iter().map(|e| e.exit_code()).collect();
+        codes.sort_unstable();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&1));
+    }
+
+    #[test]
+    fn test_not_installed_message() {
+        let err = GovmError::NotInstalled("1.22.0".to_string());
+        assert_eq!(
+            err.to_string(),
+            "Go 1.22.0 is not installed. Run 'govm install 1.22.0' first."
+        );
+    }
+}
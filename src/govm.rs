@@ -1,15 +1,47 @@
-use anyhow::{bail, Context, Result};
 use colored::*;
+use futures_util::future::join_all;
+use indicatif::HumanBytes;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
-use std::io::{self, Write};
-use std::path::PathBuf;
+use std::io::{self, IsTerminal, Write};
+use std::os::unix::fs::symlink;
+use std::path::{Path, PathBuf};
 use std::process::{exit, Command};
-
-use crate::constants::{GO_BINARIES, GO_DOWNLOAD_BASE};
-use crate::download::{download_file, extract_archive, fetch_remote_versions, get_platform};
-use crate::shim::{create_all_shims, ensure_shims};
-use crate::version::{self, find_local_version, get_global_version, normalize, parse};
+use std::time::{Duration, SystemTime};
+
+use crate::auth;
+use crate::build;
+use crate::config::{self, Config};
+use crate::constants::{GO_BINARIES, GO_SOURCE_REPO, PLATFORM_ENV};
+use crate::disk;
+use crate::download::{
+    download_file, extract_archive, extract_archive_flat, fetch_text, get_platform, move_goroot,
+    suspend_progress,
+};
+use crate::error::{GovmError, Result};
+use crate::hooks::{self, Event, Toolchain};
+use crate::http::{self, HttpConfig};
+use crate::isolate::{self, Isolation};
+use crate::lock::{self, Lock, LOCK_FILE};
+use crate::manifest;
+use crate::migrate::Tool;
+use crate::projects;
+use crate::shim::{create_all_shims, ensure_shims, ensure_shims_for};
+use crate::source::{self, ArtifactFetcher, VersionSource};
+use crate::support::{self, SupportStatus};
+use crate::tools::{self, PinnedTool};
+use crate::types::{GoFile, GoVersion};
+use crate::usage;
+use crate::version::{
+    self, find_local_version, find_local_version_file, get_global_version, normalize, parse,
+};
+
+/// How often `exec` may repeat a support warning for the same version
+const EXEC_WARNING_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Changed files `verify` lists per category before summarizing the rest
+const VERIFY_LIST_LIMIT: usize = 10;
 
 /// Main GoVM manager struct
 pub struct GoVM {
@@ -17,36 +49,180 @@ pub struct GoVM {
     pub versions_dir: PathBuf,
     pub shims_dir: PathBuf,
     pub global_version_file: PathBuf,
+    pub cache_dir: PathBuf,
+    pub projects_file: PathBuf,
+    pub usage_dir: PathBuf,
+    /// Per-version GOPATH/GOBIN workspaces, used when install.isolate is set
+    pub gopath_dir: PathBuf,
+    /// Tools (`module@version` per line) installed with every new Go version
+    pub default_tools_file: PathBuf,
+    /// Project-pinned tools, cached per (Go version, tool, tool version)
+    pub tools_dir: PathBuf,
+    /// {pre,post}-{install,uninstall,use} scripts
+    pub hooks_dir: PathBuf,
+    /// File hashes recorded at install time, one file per version
+    pub manifests_dir: PathBuf,
+    pub config_file: PathBuf,
+    /// Settings from config.toml with environment overrides applied
+    pub config: Config,
+    pub version_source: Box<dyn VersionSource>,
+    pub fetcher: Box<dyn ArtifactFetcher>,
 }
 
 impl GoVM {
-    /// Create a new GoVM instance
+    /// Create a new GoVM instance rooted at ~/.govm
     pub fn new() -> Result<Self> {
-        let home = dirs::home_dir().context("Could not find home directory")?;
-        let root_dir = home.join(".govm");
+        let home = dirs::home_dir().ok_or(GovmError::HomeNotFound)?;
+        Self::with_root(home.join(".govm"))
+    }
+
+    /// Create a GoVM instance rooted at a custom directory
+    pub fn with_root(root_dir: PathBuf) -> Result<Self> {
         let versions_dir = root_dir.join("versions");
         let shims_dir = root_dir.join("shims");
         let global_version_file = root_dir.join("version");
+        let cache_dir = root_dir.join("cache");
+        let projects_file = root_dir.join("projects");
+        let usage_dir = root_dir.join("usage");
+        let gopath_dir = root_dir.join("gopath");
+        let default_tools_file = root_dir.join("default-tools");
+        let tools_dir = root_dir.join("tools");
+        let hooks_dir = root_dir.join("hooks");
+        let manifests_dir = root_dir.join("manifests");
+        let config_file = root_dir.join("config.toml");
+        // A broken config.toml must not stop every `go` call, nor `govm config` fixing it
+        let config = Config::load(&config_file).unwrap_or_else(|e| {
+            eprintln!("{} {}. Using the defaults.", "⚠".yellow(), e);
+            Config::from_env()
+        });
 
         // Create directories if they don't exist
         fs::create_dir_all(&versions_dir)?;
         fs::create_dir_all(&shims_dir)?;
 
+        let (version_source, fetcher) = source::from_config(&config.mirror);
+        http::init(HttpConfig::from_settings(&config.network));
+
         Ok(Self {
             root_dir,
             versions_dir,
             shims_dir,
             global_version_file,
+            cache_dir,
+            projects_file,
+            usage_dir,
+            gopath_dir,
+            default_tools_file,
+            tools_dir,
+            hooks_dir,
+            manifests_dir,
+            config_file,
+            config,
+            version_source,
+            fetcher,
         })
     }
 
+    /// Replace where versions and archives are fetched from
+    pub fn with_source(
+        mut self,
+        version_source: Box<dyn VersionSource>,
+        fetcher: Box<dyn ArtifactFetcher>,
+    ) -> Self {
+        self.version_source = version_source;
+        self.fetcher = fetcher;
+        self
+    }
+
+    /// Fetch the remote version index, keeping a cached copy for offline checks
+    async fn fetch_versions(&self) -> Result<Vec<GoVersion>> {
+        if let Some(versions) = self.fresh_cached_versions() {
+            return Ok(versions);
+        }
+        let versions = self.version_source.fetch_versions().await?;
+        // Caching is best effort - a read-only cache dir shouldn't break installs
+        let _ = fs::create_dir_all(&self.cache_dir).and_then(|_| {
+            fs::write(
+                self.cache_dir.join("remote-versions.json"),
+                serde_json::to_string(&versions)?,
+            )
+        });
+        Ok(versions)
+    }
+
+    /// The cached index, if it is younger than `cache.index_ttl`
+    fn fresh_cached_versions(&self) -> Option<Vec<GoVersion>> {
+        let ttl = usage::parse_age(self.config.cache.index_ttl.as_deref()?).ok()?;
+        let age = fs::metadata(self.cache_dir.join("remote-versions.json"))
+            .and_then(|m| m.modified())
+            .ok()?
+            .elapsed()
+            .ok()?;
+        if age < ttl {
+            self.cached_versions()
+        } else {
+            None
+        }
+    }
+
+    /// Read the remote version index cached by the last fetch
+    fn cached_versions(&self) -> Option<Vec<GoVersion>> {
+        let content = fs::read_to_string(self.cache_dir.join("remote-versions.json")).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    /// Build a warning if the version is end-of-life or has a newer patch release
+    fn support_warning(&self, version: &str) -> Option<String> {
+        if !self.config.warnings.support {
+            return None;
+        }
+        let remote = self.cached_versions()?;
+        match support::check(version, &remote)? {
+            SupportStatus::Supported => None,
+            SupportStatus::Outdated { latest } => Some(format!(
+                "Go {} has a newer patch release: {} (run 'govm use {}')",
+                version, latest, latest
+            )),
+            SupportStatus::EndOfLife { latest } => Some(format!(
+                "Go {} is no longer supported and receives no security fixes. Latest is {}",
+                version, latest
+            )),
+        }
+    }
+
+    /// Print a support warning for the version on stderr
+    fn warn_if_unsupported(&self, version: &str) {
+        if let Some(warning) = self.support_warning(version) {
+            eprintln!("{} {}", "⚠".yellow(), warning);
+        }
+    }
+
+    /// Like `warn_if_unsupported`, but at most once a day per version (used by exec)
+    fn warn_if_unsupported_daily(&self, version: &str) {
+        let stamp = self.cache_dir.join(format!("support-warning-{}", version));
+        let recently_warned = fs::metadata(&stamp)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| SystemTime::now().duration_since(t).ok())
+            .is_some_and(|age| age < EXEC_WARNING_INTERVAL);
+        if recently_warned {
+            return;
+        }
+
+        if let Some(warning) = self.support_warning(version) {
+            eprintln!("{} {}", "⚠".yellow(), warning);
+            let _ = fs::create_dir_all(&self.cache_dir).and_then(|_| fs::write(&stamp, ""));
+        }
+    }
+
     /// Get list of installed Go versions
     pub fn get_installed_versions(&self) -> Result<Vec<String>> {
         let mut versions = Vec::new();
         if self.versions_dir.exists() {
             for entry in fs::read_dir(&self.versions_dir)? {
                 let entry = entry?;
-                if entry.file_type()?.is_dir() {
+                // Follow symlinks so toolchains registered with `govm link` are listed
+                if entry.path().is_dir() {
                     if let Some(name) = entry.file_name().to_str() {
                         versions.push(name.to_string());
                     }
@@ -60,7 +236,13 @@ impl GoVM {
 
     /// Check if a version is installed
     pub fn is_version_installed(&self, version: &str) -> bool {
-        self.versions_dir.join(version).exists()
+        // symlink_metadata so a link whose target disappeared can still be uninstalled
+        fs::symlink_metadata(self.versions_dir.join(version)).is_ok()
+    }
+
+    /// The GOROOT a version registered with `govm link` points to
+    pub fn linked_path(&self, version: &str) -> Option<PathBuf> {
+        fs::read_link(self.versions_dir.join(version)).ok()
     }
 
     /// Get path to a binary in a specific version
@@ -68,9 +250,102 @@ impl GoVM {
         self.versions_dir.join(version).join("bin").join(binary)
     }
 
+    /// Find a binary in a version's GOROOT, then the current project's pinned
+    /// tools, then the version's isolated GOBIN
+    fn find_binary(&self, version: &str, binary: &str) -> Option<PathBuf> {
+        let goroot_bin = self.get_version_bin_path(version, binary);
+        // `go` itself is found here, without walking up the project on every call
+        if goroot_bin.exists() {
+            return Some(goroot_bin);
+        }
+        let pinned = env::current_dir()
+            .ok()
+            .and_then(|dir| tools::find_project_tools(&dir))
+            .and_then(|(_, pinned)| pinned.into_iter().find(|t| t.binary_name() == binary))
+            .map(|t| t.cache_dir(&self.tools_dir, version).join(binary));
+
+        pinned
+            .into_iter()
+            .chain([self.gopath_dir.join(version).join("bin").join(binary)])
+            .find(|path| path.exists())
+    }
+
+    /// Remove a version's isolated workspace and cached tools, if it has any
+    fn remove_workspace(&self, version: &str) -> Result<()> {
+        for dir in [self.gopath_dir.join(version), self.tools_dir.join(version)] {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
+        }
+        Ok(())
+    }
+
     /// Resolve the current Go version
     pub fn resolve_version(&self) -> Result<Option<String>> {
-        version::resolve(&self.global_version_file)
+        let version = version::resolve_with(&self.global_version_file, self.config.resolve.go_mod)?;
+        Ok(version.map(|v| self.newest_installed_patch(&v).unwrap_or(v)))
+    }
+
+    /// For a minor version like "1.22" (as in go.mod), the newest installed 1.22.x
+    fn newest_installed_patch(&self, version: &str) -> Option<String> {
+        if self.is_version_installed(version) || version.matches('.').count() != 1 {
+            return None;
+        }
+        let prefix = format!("{}.", version);
+        self.get_installed_versions()
+            .ok()?
+            .into_iter()
+            .find(|v| v.starts_with(&prefix) && !parse(v).3.contains('-'))
+    }
+
+    /// The installed version satisfying `version`: itself, or the newest installed patch
+    fn installed_match(&self, version: &str) -> Option<String> {
+        if self.is_version_installed(version) {
+            Some(version.to_string())
+        } else {
+            self.newest_installed_patch(version)
+        }
+    }
+
+    /// The per-version workspace mode from `install.isolate`
+    fn isolation(&self) -> Isolation {
+        Isolation::parse(self.config.install.isolate.as_deref())
+    }
+
+    /// Run the pre-hook for `event` on `version`; an error aborts the operation
+    fn pre_hook(&self, event: Event, version: &str) -> Result<()> {
+        let goroot = self
+            .linked_path(version)
+            .unwrap_or_else(|| self.versions_dir.join(version));
+        let (os, arch) = version::platform_of(version);
+        hooks::run_pre(
+            &self.hooks_dir,
+            event,
+            &Toolchain {
+                version,
+                goroot: &goroot,
+                os: &os,
+                arch: &arch,
+            },
+        )
+    }
+
+    /// Run the post-hook for `event` on `version`
+    fn post_hook(&self, event: Event, version: &str) {
+        let goroot = self
+            .linked_path(version)
+            .unwrap_or_else(|| self.versions_dir.join(version));
+        let (os, arch) = version::platform_of(version);
+        hooks::run_post(
+            &self.hooks_dir,
+            event,
+            &Toolchain {
+                version,
+                goroot: &goroot,
+                os: &os,
+                arch: &arch,
+            },
+        );
     }
 
     /// Get the global version
@@ -83,207 +358,1395 @@ impl GoVM {
         let version = normalize(version);
 
         if !self.is_version_installed(&version) {
-            bail!(
-                "Go {} is not installed. Run 'govm install {}' first.",
+            return Err(GovmError::NotInstalled(version));
+        }
+
+        self.pre_hook(Event::Use, &version)?;
+        fs::write(&self.global_version_file, format!("{}\n", version))?;
+        println!(
+            "{} Set global Go version to {}",
+            "✓".green(),
+            version.cyan()
+        );
+        self.post_hook(Event::Use, &version);
+        self.warn_if_unsupported(&version);
+        Ok(())
+    }
+
+    /// Set the local Go version (creates .go-version file)
+    pub fn set_local_version(&self, version: &str) -> Result<()> {
+        let version = normalize(version);
+
+        if !self.is_version_installed(&version) {
+            return Err(GovmError::NotInstalled(version));
+        }
+
+        self.pre_hook(Event::Use, &version)?;
+        let current_dir = env::current_dir()?;
+        let version_file = current_dir.join(".go-version");
+        fs::write(&version_file, format!("{}\n", version))?;
+        projects::record(&self.projects_file, &current_dir)?;
+        println!(
+            "{} Set local Go version to {} ({})",
+            "✓".green(),
+            version.cyan(),
+            version_file.display().to_string().dimmed()
+        );
+        self.post_hook(Event::Use, &version);
+        self.warn_if_unsupported(&version);
+        Ok(())
+    }
+
+    /// Use a specific version - installs if needed, then sets as global or local
+    pub async fn use_version(&self, version: &str, local: bool) -> Result<()> {
+        let version = normalize(version);
+
+        // Install if not already installed
+        if !self.is_version_installed(&version) {
+            if !self.config.install.auto_install {
+                return Err(GovmError::NotInstalled(version));
+            }
+            println!(
+                "{} Go {} is not installed, installing...",
+                "→".blue(),
+                version.cyan()
+            );
+            self.install_version(&version).await?;
+        }
+
+        // Set as local or global
+        self.pre_hook(Event::Use, &version)?;
+        if local {
+            let current_dir = env::current_dir()?;
+            let version_file = current_dir.join(".go-version");
+            fs::write(&version_file, format!("{}\n", version))?;
+            projects::record(&self.projects_file, &current_dir)?;
+            println!(
+                "{} Now using Go {} {}",
+                "✓".green(),
+                version.cyan(),
+                format!("(local: {})", version_file.display()).dimmed()
+            );
+        } else {
+            fs::write(&self.global_version_file, format!("{}\n", version))?;
+            println!(
+                "{} Now using Go {} {}",
+                "✓".green(),
+                version.cyan(),
+                "(global)".dimmed()
+            );
+        }
+        self.post_hook(Event::Use, &version);
+
+        self.warn_if_unsupported(&version);
+        Ok(())
+    }
+
+    /// Install a specific Go version. A minor version like "1.22" that isn't a
+    /// release itself means the newest 1.22.x.
+    pub async fn install_version(&self, version: &str) -> Result<()> {
+        let (os, arch) = get_platform();
+        self.install_version_for(version, os, arch).await
+    }
+
+    /// Install a Go version built for another OS/architecture.
+    ///
+    /// Non-host builds live in their own directory, e.g. "1.22.3-linux-arm64".
+    pub async fn install_version_for(&self, version: &str, os: &str, arch: &str) -> Result<()> {
+        let version = normalize(version);
+        if self.already_installed(&version::with_platform(&version, os, arch)) {
+            return Ok(());
+        }
+
+        let lock = self.project_lock()?;
+        println!("{} Fetching Go version information...", "→".blue());
+        let versions = self.fetch_versions().await?;
+        let release = resolve_release(&versions, &version);
+        let dir_name = version::with_platform(&release, os, arch);
+        if release != version && self.already_installed(&dir_name) {
+            return Ok(());
+        }
+        self.install_release(&versions, &release, os, arch, lock.as_ref())
+            .await?;
+        // Cross builds never become the global version
+        if dir_name == release {
+            self.set_global_if_first(&release)?;
+        }
+        Ok(())
+    }
+
+    /// Put back a version whose `reinstall` died between moving it aside and
+    /// moving the new copy in. Returns whether it was restored.
+    fn restore_interrupted_reinstall(&self, version: &str) -> Result<bool> {
+        let old = self
+            .root_dir
+            .join("reinstall")
+            .join(format!("{}.old", version));
+        if !old.exists() {
+            return Ok(false);
+        }
+        if self.versions_dir.join(version).exists() {
+            // The swap went through; only the cleanup didn't
+            fs::remove_dir_all(&old)?;
+            return Ok(false);
+        }
+        fs::rename(&old, self.versions_dir.join(version))?;
+        println!(
+            "{} Restored Go {} from an interrupted reinstall",
+            "→".blue(),
+            version.cyan()
+        );
+        Ok(true)
+    }
+
+    /// Say so if `dir_name` is installed
+    fn already_installed(&self, dir_name: &str) -> bool {
+        let installed = self.is_version_installed(dir_name);
+        if installed {
+            println!(
+                "{} Go {} is already installed",
+                "✓".green(),
+                dir_name.cyan()
+            );
+        }
+        installed
+    }
+
+    /// Make a new host version global if it is the only one installed
+    fn set_global_if_first(&self, version: &str) -> Result<()> {
+        let installed = self.get_installed_versions()?;
+        if installed.len() == 1 && self.config.install.set_global_on_first_install {
+            self.set_global_version(version)?;
+        }
+        Ok(())
+    }
+
+    /// Install a release from the index `versions`, holding its archive to
+    /// `lock` if that locks this version
+    async fn install_release(
+        &self,
+        versions: &[GoVersion],
+        version: &str,
+        os: &str,
+        arch: &str,
+        lock: Option<&(PathBuf, Lock)>,
+    ) -> Result<()> {
+        let dir_name = version::with_platform(version, os, arch);
+        let Some(file) = self.prepare_release(versions, version, os, arch, lock)? else {
+            return Ok(());
+        };
+        self.unpack_release(&file, &dir_name).await?;
+        self.finish_release(&dir_name, dir_name == version);
+        Ok(())
+    }
+
+    /// Find the archive of a release and run the pre-install hook. `None` if an
+    /// interrupted reinstall left the version behind and it was put back instead.
+    fn prepare_release(
+        &self,
+        versions: &[GoVersion],
+        version: &str,
+        os: &str,
+        arch: &str,
+        lock: Option<&(PathBuf, Lock)>,
+    ) -> Result<Option<GoFile>> {
+        let dir_name = version::with_platform(version, os, arch);
+        if self.restore_interrupted_reinstall(&dir_name)? {
+            return Ok(None);
+        }
+
+        let mut file = find_archive(versions, version, os, arch)?;
+        if let Some((path, lock)) = lock {
+            if lock.version == version {
+                file.sha256 = locked_checksum(path, lock, &file)?;
+            }
+        }
+
+        self.pre_hook(Event::Install, &dir_name)?;
+        Ok(Some(file))
+    }
+
+    /// Download and unpack a prepared release into `versions/<dir_name>`
+    async fn unpack_release(&self, file: &GoFile, dir_name: &str) -> Result<()> {
+        let checksum = self
+            .download_archive(file, dir_name, &self.versions_dir.join(dir_name))
+            .await?;
+        manifest::write_archive_checksum(&self.manifests_dir, dir_name, &checksum)?;
+
+        // Create shims only if they don't exist
+        ensure_shims(&self.shims_dir)
+    }
+
+    /// Everything after unpacking a release: default tools, the post-install
+    /// hook and the manifest. None of it fails the install.
+    fn finish_release(&self, dir_name: &str, is_host: bool) {
+        println!(
+            "{} Go {} installed successfully!",
+            "✓".green(),
+            dir_name.cyan()
+        );
+        // Cross-platform builds can't run here
+        if is_host {
+            self.default_tools_after_install(dir_name);
+        }
+        self.post_hook(Event::Install, dir_name);
+        self.record_manifest(dir_name);
+    }
+
+    /// Install the version the current project requires: its .go-version or
+    /// govm.lock, or else the nearest go.mod
+    pub async fn install_project_version(&self) -> Result<()> {
+        let (file, version) = match find_local_version_file()? {
+            Some(found) => found,
+            None => version::find_go_mod_version()?.ok_or(GovmError::NoProjectVersion)?,
+        };
+        if let Some(installed) = self.installed_match(&version) {
+            println!(
+                "{} Go {} is already installed {}",
+                "✓".green(),
+                installed.cyan(),
+                format!("(required by {})", file.display()).dimmed()
+            );
+            return Ok(());
+        }
+
+        let versions = self.fetch_versions().await?;
+        let release = release_for(&versions, &version).unwrap_or(version);
+        println!(
+            "{} {} requires Go {}",
+            "→".blue(),
+            file.display(),
+            release.cyan()
+        );
+        let (os, arch) = get_platform();
+        let lock = self.project_lock()?;
+        self.install_release(&versions, &release, os, arch, lock.as_ref())
+            .await?;
+        self.set_global_if_first(&release)
+    }
+
+    /// Install several versions at once, downloading and unpacking them in
+    /// parallel. Versions are resolved like `install_version`.
+    pub async fn install_versions(&self, versions: &[String]) -> Result<()> {
+        let lock = self.project_lock()?;
+        println!("{} Fetching Go version information...", "→".blue());
+        let index = self.fetch_versions().await?;
+
+        let mut installs: Vec<(String, Option<(PathBuf, Lock)>)> = Vec::new();
+        for version in versions {
+            let release = resolve_release(&index, &normalize(version));
+            if !self.already_installed(&release) && !installs.iter().any(|(v, _)| *v == release) {
+                let lock = lock.as_ref().filter(|(_, l)| l.version == release).cloned();
+                installs.push((release, lock));
+            }
+        }
+        if installs.is_empty() {
+            return Ok(());
+        }
+        self.install_many(&index, installs).await
+    }
+
+    /// Install every version required by projects under `dir`, concurrently
+    pub async fn install_all_projects(&self, dir: &Path) -> Result<()> {
+        let mut references = Vec::new();
+        projects::walk(dir, &mut |dir| {
+            references.extend(projects::read_project_version(dir));
+        });
+        if references.is_empty() {
+            println!(
+                "{} No projects pin a Go version under {}",
+                "→".blue(),
+                dir.display()
+            );
+            return Ok(());
+        }
+
+        // Only needed to pick a patch release for versions like "1.22" that aren't installed
+        let versions = if references
+            .iter()
+            .any(|r| self.installed_match(&r.version).is_none())
+        {
+            self.fetch_versions().await?
+        } else {
+            Vec::new()
+        };
+        let mut required: BTreeMap<String, Vec<PathBuf>> = BTreeMap::new();
+        let mut locks: BTreeMap<String, (PathBuf, Lock)> = BTreeMap::new();
+        for reference in references {
+            let version = self
+                .installed_match(&reference.version)
+                .or_else(|| release_for(&versions, &reference.version))
+                .unwrap_or(reference.version);
+            // The lock holds even when a .go-version next to it supplied the version
+            let lock_file = reference.file.with_file_name(LOCK_FILE);
+            if lock_file.is_file() {
+                let lock = Lock::read(&lock_file)?;
+                if lock.version == version {
+                    locks.entry(version.clone()).or_insert((lock_file, lock));
+                }
+            }
+            required.entry(version).or_default().push(reference.file);
+        }
+
+        println!(
+            "{}",
+            format!("Versions required under {}:", dir.display()).bold()
+        );
+        println!();
+        for (version, files) in &required {
+            let status = if self.is_version_installed(version) {
+                "installed".green().to_string()
+            } else {
+                "missing".yellow().to_string()
+            };
+            println!("  {} {}", version.cyan(), format!("({})", status).dimmed());
+            for file in files {
+                println!("      {}", file.display().to_string().dimmed());
+            }
+        }
+        println!();
+
+        let missing: Vec<(String, Option<(PathBuf, Lock)>)> = required
+            .into_keys()
+            .filter(|v| !self.is_version_installed(v))
+            .map(|v| {
+                let lock = locks.remove(&v);
+                (v, lock)
+            })
+            .collect();
+        if missing.is_empty() {
+            println!("{} All required versions are installed", "✓".green());
+            return Ok(());
+        }
+        self.install_many(&versions, missing).await
+    }
+
+    /// Install several versions for this platform concurrently and report which failed
+    async fn install_many(
+        &self,
+        versions: &[GoVersion],
+        installs: Vec<(String, Option<(PathBuf, Lock)>)>,
+    ) -> Result<()> {
+        let (os, arch) = get_platform();
+        let first_install = self.get_installed_versions()?.is_empty();
+        // Hooks, default tools and manifests block, so only downloading and
+        // unpacking run concurrently; the rest runs one install at a time
+        let prepared: Vec<_> = installs
+            .iter()
+            .map(|(version, lock)| self.prepare_release(versions, version, os, arch, lock.as_ref()))
+            .collect();
+        let results: Vec<Result<bool>> = join_all(installs.iter().zip(prepared).map(
+            |((version, _), prepared)| async move {
+                let Some(file) = prepared? else {
+                    return Ok(false);
+                };
+                self.unpack_release(&file, version).await?;
+                Ok(true)
+            },
+        ))
+        .await;
+        for ((version, _), result) in installs.iter().zip(&results) {
+            if let Ok(true) = result {
+                self.finish_release(version, true);
+            }
+        }
+
+        let mut failed = Vec::new();
+        println!();
+        for ((version, _), result) in installs.iter().zip(&results) {
+            match result {
+                Ok(_) => println!("  {} {}", "✓".green(), version.cyan()),
+                Err(e) => {
+                    println!("  {} {} {}", "✗".red(), version.cyan(), e);
+                    failed.push(version.clone());
+                }
+            }
+        }
+        println!(
+            "{} Installed {} of {} versions",
+            if failed.is_empty() {
+                "✓".green()
+            } else {
+                "✗".red()
+            },
+            installs.len() - failed.len(),
+            installs.len()
+        );
+
+        // Decided once all are done; concurrent installs can't each tell if they were first
+        if first_install && self.config.install.set_global_on_first_install {
+            let succeeded = installs.iter().zip(&results).find(|(_, r)| r.is_ok());
+            if let Some(((version, _), _)) = succeeded {
+                self.set_global_version(version)?;
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(GovmError::InstallsFailed(failed))
+        }
+    }
+
+    /// Build Go from source and install it.
+    ///
+    /// `reference` is a release with a source archive in the index (installed
+    /// under its version), or a commit or branch of the Go repository
+    /// (installed as e.g. "tip-abc1234"). The newest installed release is used
+    /// to bootstrap the build.
+    pub async fn install_from_source(&self, reference: &str) -> Result<()> {
+        let bootstrap = self.bootstrap_toolchain()?;
+
+        println!("{} Fetching Go version information...", "→".blue());
+        let version = normalize(reference);
+        // Only what the index lacks is looked up as a commit or branch
+        let release = self
+            .fetch_versions()
+            .await?
+            .into_iter()
+            .find(|v| normalize(&v.version) == version)
+            .and_then(|v| v.files.into_iter().find(|f| f.kind == "source"));
+
+        let temp_dir = self.root_dir.join("temp_extract");
+        // Built here and moved into place when done, so an interrupted build
+        // never looks installed
+        let staging_dir = self.root_dir.join("build");
+        let staged = |name: &str| -> Result<PathBuf> {
+            let dir = staging_dir.join(name);
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+            }
+            fs::create_dir_all(&staging_dir)?;
+            Ok(dir)
+        };
+        let (dir_name, build_dir) = match release {
+            Some(file) => {
+                if self.is_version_installed(&version) {
+                    println!("{} Go {} is already installed", "✓".green(), version.cyan());
+                    return Ok(());
+                }
+                self.pre_hook(Event::Install, &version)?;
+                let build_dir = staged(&version)?;
+                let archive_path = self.root_dir.join(&file.filename);
+                println!("{} Downloading Go {} source...", "↓".blue(), version.cyan());
+                self.fetcher.fetch(&file, &archive_path).await?;
+                println!("{} Extracting archive...", "⚙".blue());
+                extract_archive(&archive_path, &build_dir, &temp_dir)?;
+                fs::remove_file(&archive_path)?;
+                (version, build_dir)
+            }
+            None => {
+                let repo = build::repo(self.config.mirror.source_repo.as_deref());
+                // A private repository authenticates like a mirror; go.googlesource.com doesn't
+                let credentials = |url: &str| {
+                    (repo != GO_SOURCE_REPO)
+                        .then(|| auth::lookup(url, &repo))
+                        .flatten()
+                };
+                let commit = if build::is_commit(reference) {
+                    reference.to_string()
+                } else {
+                    let url = format!("{}/+/{}?format=JSON", repo, reference);
+                    let body = fetch_text(&url, credentials(&url).as_ref()).await;
+                    let body = body.map_err(|e| match e {
+                        GovmError::HttpStatus { status: 404, .. } => {
+                            GovmError::VersionNotFound(reference.to_string())
+                        }
+                        e => e,
+                    })?;
+                    build::parse_commit(&body)
+                        .ok_or_else(|| GovmError::VersionNotFound(reference.to_string()))?
+                };
+
+                let dir_name = build::tip_name(&commit);
+                if self.is_version_installed(&dir_name) {
+                    println!(
+                        "{} Go {} is already installed",
+                        "✓".green(),
+                        dir_name.cyan()
+                    );
+                    return Ok(());
+                }
+                self.pre_hook(Event::Install, &dir_name)?;
+                let build_dir = staged(&dir_name)?;
+                let archive_path = self.root_dir.join(format!("{}.src.tar.gz", dir_name));
+                println!(
+                    "{} Downloading Go source at {}...",
+                    "↓".blue(),
+                    commit.cyan()
+                );
+                let url = format!("{}/+archive/{}.tar.gz", repo, commit);
+                download_file(&url, &archive_path, 0, credentials(&url).as_ref()).await?;
+                println!("{} Extracting archive...", "⚙".blue());
+                extract_archive_flat(&archive_path, &build_dir)?;
+                fs::remove_file(&archive_path)?;
+                build::write_devel_version(&build_dir, &commit)?;
+                (dir_name, build_dir)
+            }
+        };
+
+        println!(
+            "{} Building Go {} (bootstrapping with {})...",
+            "⚙".blue(),
+            dir_name.cyan(),
+            bootstrap.display()
+        );
+        if let Err(e) = build::make(&build_dir, &bootstrap) {
+            let _ = fs::remove_dir_all(&build_dir);
+            return Err(e);
+        }
+        fs::rename(&build_dir, self.versions_dir.join(&dir_name))?;
+
+        ensure_shims(&self.shims_dir)?;
+        println!("{} Go {} built successfully!", "✓".green(), dir_name.cyan());
+        self.default_tools_after_install(&dir_name);
+        self.post_hook(Event::Install, &dir_name);
+        self.record_manifest(&dir_name);
+        Ok(())
+    }
+
+    /// Download an archive, check it against `file.sha256` (when the index has
+    /// one) and unpack its GOROOT to `dest`. Returns the archive's sha256.
+    async fn download_archive(&self, file: &GoFile, name: &str, dest: &Path) -> Result<String> {
+        let archive_path = self.root_dir.join(&file.filename);
+        // One per install, so concurrent installs don't share it
+        let temp_dir = self.root_dir.join("temp_extract").join(name);
+
+        // Both paths gunzip, streaming or not
+        ensure_tarball(file, name)?;
+        suspend_progress(|| println!("{} Downloading Go {}...", "↓".blue(), name.cyan()));
+        if self.config.install.stream {
+            return self.download_unpacked(file, &temp_dir, dest).await;
+        }
+        self.fetcher.fetch(file, &archive_path).await?;
+
+        let checksum = manifest::hash_file(&archive_path)?;
+        if !file.sha256.is_empty() && !checksum.eq_ignore_ascii_case(&file.sha256) {
+            let _ = fs::remove_file(&archive_path);
+            return Err(GovmError::ChecksumMismatch {
+                file: file.filename.clone(),
+                expected: file.sha256.clone(),
+                actual: checksum,
+            });
+        }
+
+        suspend_progress(|| println!("{} Extracting Go {}...", "⚙".blue(), name.cyan()));
+        // Off the async threads, so parallel installs unpack in parallel too
+        let (archive, dest) = (archive_path.clone(), dest.to_path_buf());
+        tokio::task::spawn_blocking(move || extract_archive(&archive, &dest, &temp_dir))
+            .await
+            .map_err(|e| GovmError::Archive(e.to_string()))??;
+
+        // Clean up archive
+        fs::remove_file(&archive_path)?;
+        Ok(checksum)
+    }
+
+    /// Like `download_archive`, but unpack the archive into `temp_dir` while
+    /// it downloads, and only move its GOROOT to `dest` if the checksum matches
+    async fn download_unpacked(
+        &self,
+        file: &GoFile,
+        temp_dir: &Path,
+        dest: &Path,
+    ) -> Result<String> {
+        // Leftovers of an interrupted install would end up in the GOROOT
+        if temp_dir.exists() {
+            fs::remove_dir_all(temp_dir)?;
+        }
+        let checksum = match self.fetcher.fetch_unpacked(file, temp_dir).await {
+            Ok(checksum) => checksum,
+            Err(e) => {
+                let _ = fs::remove_dir_all(temp_dir);
+                return Err(e);
+            }
+        };
+        if !file.sha256.is_empty() && !checksum.eq_ignore_ascii_case(&file.sha256) {
+            let _ = fs::remove_dir_all(temp_dir);
+            return Err(GovmError::ChecksumMismatch {
+                file: file.filename.clone(),
+                expected: file.sha256.clone(),
+                actual: checksum,
+            });
+        }
+        move_goroot(temp_dir, dest, &file.filename)?;
+        Ok(checksum)
+    }
+
+    /// The nearest govm.lock, unless GOVM_VERSION overrides the project's version
+    fn project_lock(&self) -> Result<Option<(PathBuf, Lock)>> {
+        if env::var("GOVM_VERSION").is_ok_and(|v| !v.is_empty()) {
+            return Ok(None);
+        }
+        match lock::find(&env::current_dir()?) {
+            Some(path) => {
+                let lock = Lock::read(&path)?;
+                Ok(Some((path, lock)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Refuse to run a toolchain the project's govm.lock doesn't vouch for
+    fn enforce_lock(&self, version: &str, dir_name: &str) -> Result<()> {
+        let Some((path, lock)) = self.project_lock()? else {
+            return Ok(());
+        };
+        // Nothing was downloaded for these, and `reinstall` can't change that
+        let not_downloaded = match self.linked_path(dir_name) {
+            Some(target) => Some(format!("is linked to {}", target.display())),
+            None => dir_name
+                .starts_with("tip-")
+                .then(|| "was built from source".to_string()),
+        };
+        if let Some(reason) = not_downloaded {
+            return Err(GovmError::Lock(format!(
+                "Go {} {}, so it can't satisfy {}. Only releases govm installed can.",
+                dir_name,
+                reason,
+                path.display()
+            )));
+        }
+        if lock.version != version {
+            return Err(GovmError::Lock(format!(
+                "{} pins Go {}, but Go {} is selected. Fix .go-version or run 'govm lock {}'.",
+                path.display(),
+                lock.version,
                 version,
                 version
+            )));
+        }
+
+        let (os, arch) = version::platform_of(dir_name);
+        let expected = lock.checksum(&os, &arch).ok_or_else(|| {
+            GovmError::Lock(format!(
+                "{} has no checksum for {}/{}",
+                path.display(),
+                os,
+                arch
+            ))
+        })?;
+        match manifest::read_archive_checksum(&self.manifests_dir, dir_name) {
+            Some(actual) if actual.eq_ignore_ascii_case(expected) => Ok(()),
+            Some(_) => Err(GovmError::Lock(format!(
+                "Go {} was installed from a different archive than {} allows. Run 'govm reinstall {}'.",
+                dir_name,
+                path.display(),
+                dir_name
+            ))),
+            None => Err(GovmError::Lock(format!(
+                "Go {} has no recorded archive checksum to check against {}. Run 'govm reinstall {}'.",
+                dir_name,
+                path.display(),
+                dir_name
+            ))),
+        }
+    }
+
+    /// Pin the project to a version and the checksums of its release archives.
+    ///
+    /// Without `version`, the project's current version is locked.
+    pub async fn lock_version(&self, version: Option<&str>) -> Result<()> {
+        let (dir, version) = match version {
+            Some(version) => (env::current_dir()?, normalize(version)),
+            None => {
+                let (file, version) =
+                    find_local_version_file()?.ok_or(GovmError::NoVersionConfigured)?;
+                let dir = file.parent().map(Path::to_path_buf).unwrap_or_default();
+                (dir, version)
+            }
+        };
+
+        println!("{} Fetching Go version information...", "→".blue());
+        let release = self
+            .fetch_versions()
+            .await?
+            .into_iter()
+            .find(|v| normalize(&v.version) == version)
+            .ok_or_else(|| GovmError::VersionNotFound(version.clone()))?;
+        let lock = Lock::from_release(&release);
+        if lock.sha256.is_empty() {
+            return Err(GovmError::Lock(format!(
+                "The version index has no checksums for Go {}",
+                version
+            )));
+        }
+
+        // Keep a .go-version next to the lock in step with it
+        let version_file = dir.join(".go-version");
+        let pinned = fs::read_to_string(&version_file).map(|c| normalize(c.trim()));
+        if pinned.is_ok_and(|pinned| pinned != version) {
+            fs::write(&version_file, format!("{}\n", version))?;
+            println!(
+                "{} Updated {} to {}",
+                "✓".green(),
+                version_file.display(),
+                version.cyan()
+            );
+        }
+
+        let lock_file = dir.join(LOCK_FILE);
+        lock.write(&lock_file)?;
+        let platforms = lock.sha256.len();
+        println!(
+            "{} Locked Go {} for {} platform{} {}",
+            "✓".green(),
+            version.cyan(),
+            platforms,
+            if platforms == 1 { "" } else { "s" },
+            format!("({})", lock_file.display()).dimmed()
+        );
+        Ok(())
+    }
+
+    /// Record the file hashes of an installed version for `govm verify`. The
+    /// version is installed either way, so errors are only reported.
+    fn record_manifest(&self, version: &str) {
+        let path = self.manifests_dir.join(version);
+        let written = manifest::hash_tree(&self.versions_dir.join(version))
+            .and_then(|manifest| manifest::write(&path, &manifest));
+        if let Err(e) = written {
+            println!(
+                "{} Could not record what Go {} installed, 'govm verify' will skip it: {}",
+                "⚠".yellow(),
+                version,
+                e
             );
         }
+    }
+
+    /// GOROOT of the newest installed release for this platform, used to bootstrap source builds
+    fn bootstrap_toolchain(&self) -> Result<PathBuf> {
+        self.get_installed_versions()?
+            .into_iter()
+            .filter(|v| {
+                let (major, _, _, suffix) = parse(v);
+                major > 0 && !suffix.contains('-')
+            })
+            .map(|v| self.versions_dir.join(v))
+            .find(|dir| dir.join("bin").join("go").exists())
+            .ok_or(GovmError::BootstrapRequired)
+    }
+
+    /// Register an existing GOROOT (e.g. /usr/local/go) as version `name`
+    pub fn link_version(&self, name: &str, path: &Path) -> Result<()> {
+        // Names must survive normalize() so `use`/`exec` find them again
+        let invalid = name.is_empty() || name.starts_with('.') || name.contains(['/', '\\']);
+        if invalid || normalize(name) != name {
+            return Err(GovmError::InvalidVersionName(name.to_string()));
+        }
+        if self.is_version_installed(name) {
+            return Err(GovmError::AlreadyInstalled(name.to_string()));
+        }
+
+        let goroot = path
+            .canonicalize()
+            .map_err(|_| GovmError::NotAGoroot(path.to_path_buf()))?;
+        if !goroot.join("bin").join("go").exists() {
+            return Err(GovmError::NotAGoroot(goroot));
+        }
+        let go_version = fs::read_to_string(goroot.join("VERSION"))
+            .ok()
+            .and_then(|v| v.lines().next().map(str::to_string))
+            .unwrap_or_else(|| "unknown version".to_string());
+
+        fs::create_dir_all(&self.versions_dir)?;
+        symlink(&goroot, self.versions_dir.join(name))?;
+
+        ensure_shims(&self.shims_dir)?;
+        println!(
+            "{} Linked {} to {} {}",
+            "✓".green(),
+            name.cyan(),
+            goroot.display(),
+            format!("({})", go_version).dimmed()
+        );
+        Ok(())
+    }
+
+    /// Import toolchains installed by another version manager.
+    ///
+    /// Toolchains are moved into versions_dir, or linked when `link` is set (or
+    /// when moving fails, e.g. across filesystems). The tool's global version
+    /// becomes govm's if none is set. Project files under `scan` that pin a
+    /// version in a format govm doesn't read are reported.
+    pub fn migrate(&self, tool: Tool, link: bool, scan: &[PathBuf]) -> Result<()> {
+        let home = dirs::home_dir().ok_or(GovmError::HomeNotFound)?;
+        let root = tool.default_root(&home);
+        let toolchains = tool.toolchains(&root);
+
+        if toolchains.is_empty() {
+            println!(
+                "{} No {} toolchains found in {}",
+                "→".blue(),
+                tool.name(),
+                root.display()
+            );
+        }
+
+        fs::create_dir_all(&self.versions_dir)?;
+        let mut imported = 0;
+        for (version, goroot) in &toolchains {
+            if self.is_version_installed(version) {
+                println!(
+                    "  {} {} {}",
+                    "-".dimmed(),
+                    version,
+                    "(already installed, skipped)".dimmed()
+                );
+                continue;
+            }
+
+            let dest = self.versions_dir.join(version);
+            let how = if !link && fs::rename(goroot, &dest).is_ok() {
+                "moved"
+            } else {
+                symlink(goroot, &dest)?;
+                "linked"
+            };
+            imported += 1;
+            println!(
+                "  {} {} {}",
+                "✓".green(),
+                version.cyan(),
+                format!("({} from {})", how, goroot.display()).dimmed()
+            );
+        }
+        if imported > 0 {
+            ensure_shims(&self.shims_dir)?;
+        }
+
+        if let Some(version) = tool.global_version(&root, &home) {
+            if self.get_global_version()?.is_none() && self.is_version_installed(&version) {
+                fs::write(&self.global_version_file, format!("{}\n", version))?;
+                println!(
+                    "{} Set global Go version to {}",
+                    "✓".green(),
+                    version.cyan()
+                );
+            }
+        }
+
+        let mut references = Vec::new();
+        for dir in scan {
+            references.extend(tool.project_files(dir));
+        }
+        if !references.is_empty() {
+            println!();
+            println!(
+                "{}",
+                format!("{} project files govm doesn't read:", tool.name()).bold()
+            );
+            for r in &references {
+                println!(
+                    "  - {} {}",
+                    r.file.display(),
+                    format!("(run 'govm local {}' there)", r.version).dimmed()
+                );
+            }
+        }
+
+        println!();
+        println!(
+            "{} Imported {} of {} {} toolchains",
+            "✓".green(),
+            imported,
+            toolchains.len(),
+            tool.name()
+        );
+        Ok(())
+    }
+
+    /// Uninstall a specific Go version
+    pub fn uninstall_version(&self, version: &str) -> Result<()> {
+        let version = normalize(version);
+
+        if !self.is_version_installed(&version) {
+            println!("{} Go {} is not installed", "✗".red(), version.cyan());
+            return Ok(());
+        }
+        self.pre_hook(Event::Uninstall, &version)?;
+
+        // Check if it's the global version
+        let global = self.get_global_version()?;
+        if global.as_ref() == Some(&version) {
+            // Remove global version file
+            if self.global_version_file.exists() {
+                fs::remove_file(&self.global_version_file)?;
+            }
+            println!("{} Cleared global version", "→".blue());
+        }
+
+        let version_dir = self.versions_dir.join(&version);
+        self.remove_workspace(&version)?;
+        if let Some(target) = self.linked_path(&version) {
+            // Only drop the link; the toolchain belongs to whoever installed it
+            fs::remove_file(&version_dir)?;
+            usage::clear(&self.usage_dir, &version)?;
+            manifest::clear(&self.manifests_dir, &version)?;
+            println!(
+                "{} Unlinked {} {}",
+                "✓".green(),
+                version.cyan(),
+                format!("({} was left in place)", target.display()).dimmed()
+            );
+            self.post_hook(Event::Uninstall, &version);
+            return Ok(());
+        }
+        fs::remove_dir_all(&version_dir)?;
+        usage::clear(&self.usage_dir, &version)?;
+        manifest::clear(&self.manifests_dir, &version)?;
+
+        println!(
+            "{} Go {} has been uninstalled",
+            "✓".green(),
+            version.cyan()
+        );
+        self.post_hook(Event::Uninstall, &version);
+
+        Ok(())
+    }
+
+    /// Check installed versions (or just `version`) against their install-time manifests
+    pub fn verify_versions(&self, version: Option<&str>) -> Result<()> {
+        let versions = match version {
+            Some(version) => {
+                let version = normalize(version);
+                if !self.is_version_installed(&version) {
+                    return Err(GovmError::NotInstalled(version));
+                }
+                vec![version]
+            }
+            None => self.get_installed_versions()?,
+        };
+
+        let mut failed = Vec::new();
+        for version in &versions {
+            if self.linked_path(version).is_some() {
+                println!(
+                    "  {} {} {}",
+                    "-".dimmed(),
+                    version,
+                    "(linked, skipped)".dimmed()
+                );
+                continue;
+            }
+            let Some(recorded) = manifest::read(&self.manifests_dir.join(version))? else {
+                println!(
+                    "  {} {} {}",
+                    "-".dimmed(),
+                    version,
+                    "(no manifest recorded, skipped)".dimmed()
+                );
+                continue;
+            };
+
+            let report = manifest::check(&self.versions_dir.join(version), &recorded)?;
+            if report.is_ok() {
+                let mut note = format!("{} files", recorded.len());
+                if !report.added.is_empty() {
+                    note.push_str(&format!(", {} added since install", report.added.len()));
+                }
+                println!(
+                    "  {} {} {}",
+                    "✓".green(),
+                    version.cyan(),
+                    format!("({})", note).dimmed()
+                );
+                continue;
+            }
+
+            println!("  {} {}", "✗".red(), version.cyan());
+            for (label, files) in [("modified", &report.modified), ("missing", &report.missing)] {
+                for file in files.iter().take(VERIFY_LIST_LIMIT) {
+                    println!("      {}: {}", label, file);
+                }
+                if files.len() > VERIFY_LIST_LIMIT {
+                    println!(
+                        "      {}",
+                        format!("... and {} more {}", files.len() - VERIFY_LIST_LIMIT, label)
+                            .dimmed()
+                    );
+                }
+            }
+            failed.push(version.clone());
+        }
 
-        fs::write(&self.global_version_file, format!("{}\n", version))?;
-        println!(
-            "{} Set global Go version to {}",
-            "✓".green(),
-            version.cyan()
-        );
-        Ok(())
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(GovmError::VerifyFailed(failed))
+        }
     }
 
-    /// Set the local Go version (creates .go-version file)
-    pub fn set_local_version(&self, version: &str) -> Result<()> {
+    /// Download a version again and swap it in for the installed one.
+    ///
+    /// The new GOROOT is unpacked next to the old one and renamed into place, so
+    /// the version is never half-installed. Global and local settings refer to
+    /// the version by name and stay as they are.
+    pub async fn reinstall_version(&self, version: &str) -> Result<()> {
         let version = normalize(version);
-
+        self.restore_interrupted_reinstall(&version)?;
         if !self.is_version_installed(&version) {
-            bail!(
-                "Go {} is not installed. Run 'govm install {}' first.",
+            return Err(GovmError::NotInstalled(version));
+        }
+        if let Some(target) = self.linked_path(&version) {
+            return Err(GovmError::CannotReinstall {
                 version,
-                version
-            );
+                reason: format!("it is linked to {}", target.display()),
+            });
+        }
+        if version.starts_with("tip-") {
+            return Err(GovmError::CannotReinstall {
+                version,
+                reason:
+                    "it was built from source; uninstall it and run 'govm install --source' again"
+                        .to_string(),
+            });
         }
 
-        let version_file = env::current_dir()?.join(".go-version");
-        fs::write(&version_file, format!("{}\n", version))?;
-        println!(
-            "{} Set local Go version to {} ({})",
-            "✓".green(),
-            version.cyan(),
-            version_file.display().to_string().dimmed()
-        );
-        Ok(())
-    }
-
-    /// Use a specific version - installs if needed, then sets as global or local
-    pub async fn use_version(&self, version: &str, local: bool) -> Result<()> {
-        let version = normalize(version);
-
-        // Install if not already installed
-        if !self.is_version_installed(&version) {
-            println!(
-                "{} Go {} is not installed, installing...",
-                "→".blue(),
-                version.cyan()
-            );
-            self.install_version(&version).await?;
+        let (os, arch) = version::platform_of(&version);
+        let release = version
+            .strip_suffix(&format!("-{}-{}", os, arch))
+            .unwrap_or(&version);
+        println!("{} Fetching Go version information...", "→".blue());
+        let versions = self.fetch_versions().await?;
+        let file = find_archive(&versions, release, &os, &arch)?;
+
+        self.pre_hook(Event::Install, &version)?;
+        let staging_dir = self.root_dir.join("reinstall");
+        let staged = staging_dir.join(&version);
+        let old = staging_dir.join(format!("{}.old", version));
+        for dir in [&staged, &old] {
+            if dir.exists() {
+                fs::remove_dir_all(dir)?;
+            }
         }
+        fs::create_dir_all(&staging_dir)?;
+        let checksum = self.download_archive(&file, &version, &staged).await?;
 
-        // Set as local or global
-        if local {
-            let version_file = env::current_dir()?.join(".go-version");
-            fs::write(&version_file, format!("{}\n", version))?;
-            println!(
-                "{} Now using Go {} {}",
-                "✓".green(),
-                version.cyan(),
-                format!("(local: {})", version_file.display()).dimmed()
-            );
-        } else {
-            fs::write(&self.global_version_file, format!("{}\n", version))?;
-            println!(
-                "{} Now using Go {} {}",
-                "✓".green(),
-                version.cyan(),
-                "(global)".dimmed()
-            );
+        let version_dir = self.versions_dir.join(&version);
+        fs::rename(&version_dir, &old)?;
+        if let Err(e) = fs::rename(&staged, &version_dir) {
+            let _ = fs::rename(&old, &version_dir);
+            return Err(e.into());
         }
+        fs::remove_dir_all(&staging_dir)?;
+        manifest::write_archive_checksum(&self.manifests_dir, &version, &checksum)?;
 
+        println!(
+            "{} Go {} reinstalled successfully!",
+            "✓".green(),
+            version.cyan()
+        );
+        self.post_hook(Event::Install, &version);
+        self.record_manifest(&version);
         Ok(())
     }
 
-    /// Install a specific Go version
-    pub async fn install_version(&self, version: &str) -> Result<()> {
-        let version = normalize(version);
+    /// Execute a command with the resolved Go version.
+    ///
+    /// `platform` (or GOVM_PLATFORM) selects a variant installed with --os/--arch.
+    pub fn exec_command(
+        &self,
+        command: &str,
+        args: &[String],
+        platform: Option<(String, String)>,
+    ) -> Result<()> {
+        let version = self
+            .resolve_version()?
+            .ok_or(GovmError::NoVersionConfigured)?;
+
+        let platform = match platform {
+            Some(p) => Some(p),
+            None => env::var(PLATFORM_ENV)
+                .ok()
+                .filter(|p| !p.is_empty())
+                .map(|p| version::parse_platform(&p))
+                .transpose()
+                .map_err(GovmError::InvalidPlatform)?,
+        };
+        let dir_name = match &platform {
+            Some((os, arch)) => version::with_platform(&version, os, arch),
+            None => version.clone(),
+        };
 
-        if self.is_version_installed(&version) {
-            println!(
-                "{} Go {} is already installed",
-                "✓".green(),
-                version.cyan()
-            );
-            return Ok(());
+        if !self.is_version_installed(&dir_name) {
+            return Err(GovmError::NotInstalled(dir_name));
         }
+        self.enforce_lock(&version, &dir_name)?;
 
-        println!("{} Fetching Go version information...", "→".blue());
-
-        let versions = fetch_remote_versions().await?;
-        let go_version = versions
-            .iter()
-            .find(|v| normalize(&v.version) == version)
-            .context(format!("Version {} not found", version))?;
+        let binary_path =
+            self.find_binary(&dir_name, command)
+                .ok_or_else(|| GovmError::CommandNotFound {
+                    command: command.to_string(),
+                    version: dir_name.clone(),
+                })?;
 
-        let (os, arch) = get_platform();
-        let file = go_version
-            .files
-            .iter()
-            .find(|f| f.os == os && f.arch == arch && f.kind == "archive")
-            .context(format!(
-                "No binary available for {} {} (Go {})",
-                os, arch, version
-            ))?;
+        self.warn_if_unsupported_daily(&version);
+        self.record_resolved_project();
+        // Best effort - never fail a go invocation over bookkeeping
+        let _ = usage::record(&self.usage_dir, &dir_name);
 
-        let download_url = format!("{}{}", GO_DOWNLOAD_BASE, file.filename);
-        let version_dir = self.versions_dir.join(&version);
-        let archive_path = self.root_dir.join(&file.filename);
-        let temp_dir = self.root_dir.join("temp_extract");
+        let isolation = self.isolation();
+        let workspace = self.gopath_dir.join(&dir_name);
 
-        println!("{} Downloading Go {}...", "↓".blue(), version.cyan());
-        download_file(&download_url, &archive_path, file.size).await?;
+        let status = self
+            .go_command(&dir_name, &binary_path)
+            .args(args)
+            .status()
+            .map_err(|source| GovmError::Exec {
+                command: command.to_string(),
+                source,
+            })?;
+
+        // Make binaries from `go install` reachable through shims
+        if isolation != Isolation::Off
+            && command == "go"
+            && args.first().is_some_and(|a| a == "install")
+        {
+            let _ = ensure_shims_for(&self.shims_dir, &isolate::installed_binaries(&workspace));
+        }
 
-        println!("{} Extracting archive...", "⚙".blue());
-        extract_archive(&archive_path, &version_dir, &temp_dir)?;
+        exit(status.code().unwrap_or(1));
+    }
 
-        // Clean up archive
-        fs::remove_file(&archive_path)?;
+    /// A command running `binary` with GOROOT (and any isolated workspace) set for `version`
+    fn go_command(&self, version: &str, binary: &Path) -> Command {
+        let mut command = Command::new(binary);
+        command
+            .env("GOROOT", self.versions_dir.join(version))
+            .env("GOTOOLCHAIN", "local") // Prevent Go from auto-downloading toolchains
+            .envs(self.isolation().env_vars(&self.gopath_dir.join(version)));
+        command
+    }
 
-        // Create shims only if they don't exist
-        ensure_shims(&self.shims_dir)?;
+    /// Run `go install` for each tool in ~/.govm/default-tools with `version`.
+    ///
+    /// Each failure is reported and returned, but never stops the other tools.
+    pub fn install_default_tools(&self, version: &str) -> Result<Vec<String>> {
+        let tools = tools::read_default_tools(&self.default_tools_file);
+        if tools.is_empty() {
+            return Ok(Vec::new());
+        }
 
         println!(
-            "{} Go {} installed successfully!",
-            "✓".green(),
+            "{} Installing default tools with Go {}...",
+            "→".blue(),
             version.cyan()
         );
+        let go = self.get_version_bin_path(version, "go");
+        let mut failed = Vec::new();
+        for tool in tools {
+            let output = self
+                .go_command(version, &go)
+                .args(["install", &tool])
+                .output();
+            let error = match output {
+                Ok(output) if output.status.success() => None,
+                Ok(output) => Some(
+                    String::from_utf8_lossy(&output.stderr)
+                        .lines()
+                        .rfind(|l| !l.trim().is_empty())
+                        .unwrap_or("go install failed")
+                        .to_string(),
+                ),
+                Err(e) => Some(e.to_string()),
+            };
+            match error {
+                None => println!("  {} {}", "✓".green(), tool),
+                Some(error) => {
+                    println!(
+                        "  {} {} {}",
+                        "✗".red(),
+                        tool,
+                        format!("({})", error).dimmed()
+                    );
+                    failed.push(tool);
+                }
+            }
+        }
 
-        // Set as global if it's the first version
-        let installed = self.get_installed_versions()?;
-        if installed.len() == 1 {
-            self.set_global_version(&version)?;
+        if self.isolation() != Isolation::Off {
+            let workspace = self.gopath_dir.join(version);
+            ensure_shims_for(&self.shims_dir, &isolate::installed_binaries(&workspace))?;
         }
+        Ok(failed)
+    }
 
-        Ok(())
+    /// Install the default tools for a new version. The Go install already
+    /// succeeded, so errors are only reported.
+    fn default_tools_after_install(&self, version: &str) {
+        if let Err(e) = self.install_default_tools(version) {
+            println!("{} {}", "⚠".yellow(), e);
+        }
     }
 
-    /// Uninstall a specific Go version
-    pub fn uninstall_version(&self, version: &str) -> Result<()> {
+    /// Install the default tools again for an installed version
+    pub fn reinstall_default_tools(&self, version: &str) -> Result<()> {
         let version = normalize(version);
-
         if !self.is_version_installed(&version) {
-            println!("{} Go {} is not installed", "✗".red(), version.cyan());
+            return Err(GovmError::NotInstalled(version));
+        }
+        if tools::read_default_tools(&self.default_tools_file).is_empty() {
+            println!(
+                "{} No default tools configured in {}",
+                "→".blue(),
+                self.default_tools_file.display()
+            );
             return Ok(());
         }
 
-        // Check if it's the global version
-        let global = self.get_global_version()?;
-        if global.as_ref() == Some(&version) {
-            // Remove global version file
-            if self.global_version_file.exists() {
-                fs::remove_file(&self.global_version_file)?;
-            }
-            println!("{} Cleared global version", "→".blue());
+        let failed = self.install_default_tools(&version)?;
+        if !failed.is_empty() {
+            return Err(GovmError::ToolsFailed(failed));
         }
+        Ok(())
+    }
 
-        let version_dir = self.versions_dir.join(&version);
-        fs::remove_dir_all(&version_dir)?;
+    /// The current project's pinned tools, and the file pinning them
+    fn project_tools(&self) -> Result<(PathBuf, Vec<PinnedTool>)> {
+        tools::find_project_tools(&env::current_dir()?).ok_or(GovmError::NoToolsPinned)
+    }
+
+    /// Install the current project's pinned tools with the resolved Go version
+    pub fn install_project_tools(&self) -> Result<()> {
+        let (file, pinned) = self.project_tools()?;
+        let version = self
+            .resolve_version()?
+            .ok_or(GovmError::NoVersionConfigured)?;
+        if !self.is_version_installed(&version) {
+            return Err(GovmError::NotInstalled(version));
+        }
 
         println!(
-            "{} Go {} has been uninstalled",
-            "✓".green(),
+            "{} Installing tools from {} with Go {}...",
+            "→".blue(),
+            file.display(),
             version.cyan()
         );
+        let go = self.get_version_bin_path(&version, "go");
+        let mut failed = Vec::new();
+        for tool in &pinned {
+            let cache_dir = tool.cache_dir(&self.tools_dir, &version);
+            if cache_dir.join(tool.binary_name()).exists() {
+                println!("  {} {} {}", "✓".green(), tool.spec(), "(cached)".dimmed());
+                continue;
+            }
+
+            let output = self
+                .go_command(&version, &go)
+                .args(["install", &tool.spec()])
+                .env("GOBIN", &cache_dir)
+                .output();
+            match output {
+                Ok(output) if output.status.success() => {
+                    println!("  {} {}", "✓".green(), tool.spec())
+                }
+                _ => {
+                    println!("  {} {}", "✗".red(), tool.spec());
+                    if let Ok(output) = output {
+                        io::stderr().write_all(&output.stderr)?;
+                    }
+                    let _ = fs::remove_dir_all(&cache_dir);
+                    failed.push(tool.spec());
+                }
+            }
+        }
+
+        let names: Vec<&str> = pinned.iter().map(PinnedTool::binary_name).collect();
+        ensure_shims_for(&self.shims_dir, &names)?;
 
+        if !failed.is_empty() {
+            return Err(GovmError::ToolsFailed(failed));
+        }
         Ok(())
     }
 
-    /// Execute a command with the resolved Go version
-    pub fn exec_command(&self, command: &str, args: &[String]) -> Result<()> {
-        let version = self.resolve_version()?.context(
-            "No Go version configured. Run 'govm global <version>' or create a .go-version file",
-        )?;
+    /// List the current project's pinned tools and whether they are installed
+    pub fn list_project_tools(&self) -> Result<()> {
+        let (file, pinned) = self.project_tools()?;
+        let version = self.resolve_version()?;
 
-        if !self.is_version_installed(&version) {
-            bail!(
-                "Go {} is not installed (required by current configuration). Run 'govm install {}'",
-                version,
-                version
-            );
+        println!("{}", format!("Tools pinned by {}:", file.display()).bold());
+        println!();
+        for tool in &pinned {
+            let installed = version.as_ref().is_some_and(|v| {
+                tool.cache_dir(&self.tools_dir, v)
+                    .join(tool.binary_name())
+                    .exists()
+            });
+            let status = if installed {
+                "installed".green().to_string()
+            } else {
+                "not installed".dimmed().to_string()
+            };
+            println!("  {} {}", tool.spec(), format!("({})", status).dimmed());
         }
+        Ok(())
+    }
 
-        let binary_path = self.get_version_bin_path(&version, command);
+    /// Show every setting, marking values that come from the environment
+    pub fn list_config(&self) -> Result<()> {
+        println!(
+            "{}",
+            format!("Settings ({}):", self.config_file.display()).bold()
+        );
+        println!();
+        for (key, value) in config::list(&self.config)? {
+            let value = match value {
+                Some(value) => display_value(&value),
+                None => "(unset)".dimmed().to_string(),
+            };
+            let overridden = config::ENV_OVERRIDES
+                .iter()
+                .find(|(k, _)| *k == key && self.config.from_env.contains(k));
+            match overridden {
+                Some((_, var)) => println!(
+                    "  {} = {} {}",
+                    key,
+                    value,
+                    format!("(from {})", var).dimmed()
+                ),
+                None => println!("  {} = {}", key, value),
+            }
+        }
+        Ok(())
+    }
 
-        if !binary_path.exists() {
-            bail!("Command '{}' not found in Go {}", command, version);
+    /// Print the effective value of a setting; nothing if it is unset
+    pub fn get_config(&self, key: &str) -> Result<()> {
+        if let Some(value) = config::get(&self.config, key)? {
+            println!("{}", display_value(&value));
         }
+        Ok(())
+    }
 
-        // Set GOROOT for the executed command
-        let goroot = self.versions_dir.join(&version);
+    /// Store a setting in config.toml
+    pub fn set_config(&self, key: &str, value: &str) -> Result<()> {
+        config::set(&self.config_file, key, value)?;
+        println!("{} Set {} = {}", "✓".green(), key.cyan(), value);
+        Ok(())
+    }
 
-        let status = Command::new(&binary_path)
-            .args(args)
-            .env("GOROOT", &goroot)
-            .env("GOTOOLCHAIN", "local")  // Prevent Go from auto-downloading toolchains
-            .status()
-            .context(format!("Failed to execute {}", command))?;
+    /// Remove a setting from config.toml, restoring its default
+    pub fn unset_config(&self, key: &str) -> Result<()> {
+        config::unset(&self.config_file, key)?;
+        println!("{} Unset {}", "✓".green(), key.cyan());
+        Ok(())
+    }
 
-        exit(status.code().unwrap_or(1));
+    /// Remember the project whose .go-version selected the version, so prune keeps it
+    fn record_resolved_project(&self) {
+        if env::var("GOVM_VERSION").is_ok() {
+            return;
+        }
+        if let Ok(Some((version_file, _))) = find_local_version_file() {
+            if let Some(dir) = version_file.parent() {
+                // Best effort - never fail a go invocation over bookkeeping
+                let _ = projects::record(&self.projects_file, dir);
+            }
+        }
     }
 
     /// Show which binary will be used
@@ -298,8 +1761,7 @@ impl GoVM {
                     );
                     return Ok(());
                 }
-                let path = self.get_version_bin_path(&version, command);
-                if path.exists() {
+                if let Some(path) = self.find_binary(&version, command) {
                     println!("{}", path.display());
                 } else {
                     println!(
@@ -377,8 +1839,19 @@ impl GoVM {
         Ok(())
     }
 
+    /// Describe a version's most recent activity: last use, or install time if never used
+    fn last_activity(&self, version: &str) -> Option<(&'static str, Duration)> {
+        if let Some(t) = usage::last_used(&self.usage_dir, version) {
+            return Some(("used", t.elapsed().unwrap_or_default()));
+        }
+        let installed = fs::metadata(self.versions_dir.join(version))
+            .and_then(|m| m.modified())
+            .ok()?;
+        Some(("installed", installed.elapsed().unwrap_or_default()))
+    }
+
     /// List installed versions
-    pub fn list_versions(&self) -> Result<()> {
+    pub fn list_versions(&self, verbose: bool) -> Result<()> {
         let versions = self.get_installed_versions()?;
         let current = self.resolve_version()?;
         let global = self.get_global_version()?;
@@ -418,11 +1891,31 @@ impl GoVM {
                 labels.push("global");
             }
 
-            let label_str = if labels.is_empty() {
+            let mut label_str = if labels.is_empty() {
                 String::new()
             } else {
                 format!(" ({})", labels.join(", ")).dimmed().to_string()
             };
+            if let Some(target) = self.linked_path(&version) {
+                label_str.push_str(&format!(" -> {}", target.display()).dimmed().to_string());
+            }
+
+            if verbose {
+                let last_used = usage::last_used(&self.usage_dir, &version)
+                    .and_then(|t| t.elapsed().ok())
+                    .map(usage::format_age)
+                    .unwrap_or_else(|| "never".to_string());
+                let padding = " ".repeat(10usize.saturating_sub(version.len()));
+                println!(
+                    "  {} {}{} {}{}",
+                    marker,
+                    version_str,
+                    padding,
+                    format!("last used: {}", last_used).dimmed(),
+                    label_str
+                );
+                continue;
+            }
 
             println!("  {} {}{}", marker, version_str, label_str);
         }
@@ -430,11 +1923,61 @@ impl GoVM {
         Ok(())
     }
 
+    /// Show disk usage of installed versions, caches and leftover temp files
+    pub fn disk_usage(&self) -> Result<()> {
+        let versions = self.get_installed_versions()?;
+
+        println!("{}", "Disk usage:".bold());
+        println!();
+
+        let mut versions_total = 0;
+        for version in &versions {
+            if self.linked_path(version).is_some() {
+                println!("  {:<12} {:>12}", version, "linked");
+                continue;
+            }
+            let size = disk::dir_size(&self.versions_dir.join(version));
+            versions_total += size;
+            println!("  {:<12} {:>12}", version, HumanBytes(size).to_string());
+        }
+        if !versions.is_empty() {
+            println!();
+        }
+
+        // Interrupted installs and builds leave their directories and archives behind
+        let mut temp_total: u64 = ["temp_extract", "build"]
+            .iter()
+            .map(|dir| disk::dir_size(&self.root_dir.join(dir)))
+            .sum();
+        for entry in fs::read_dir(&self.root_dir)?.flatten() {
+            if entry.file_name().to_string_lossy().ends_with(".tar.gz") {
+                temp_total += disk::dir_size(&entry.path());
+            }
+        }
+        let cache_total = disk::dir_size(&self.cache_dir);
+        let total = disk::dir_size(&self.root_dir);
+
+        for (label, size) in [
+            ("versions", versions_total),
+            ("cache", cache_total),
+            ("temp", temp_total),
+        ] {
+            println!("  {:<12} {:>12}", label, HumanBytes(size).to_string());
+        }
+        println!(
+            "  {:<12} {:>12}",
+            "total".bold(),
+            HumanBytes(total).to_string().bold()
+        );
+
+        Ok(())
+    }
+
     /// List remote available versions
     pub async fn list_remote_versions(&self, all: bool, limit: usize) -> Result<()> {
         println!("{} Fetching available Go versions...", "→".blue());
 
-        let versions = fetch_remote_versions().await?;
+        let versions = self.fetch_versions().await?;
         let installed = self.get_installed_versions()?;
         let current = self.resolve_version()?;
 
@@ -494,6 +2037,21 @@ impl GoVM {
             println!("  {} {}", "✓".green(), binary);
         }
 
+        // Binaries installed into per-version GOBINs
+        let mut tools = Vec::new();
+        for version in self.get_installed_versions()? {
+            for tool in isolate::installed_binaries(&self.gopath_dir.join(&version)) {
+                if !tools.contains(&tool) && !GO_BINARIES.contains(&tool.as_str()) {
+                    tools.push(tool);
+                }
+            }
+        }
+        tools.sort();
+        ensure_shims_for(&self.shims_dir, &tools)?;
+        for tool in &tools {
+            println!("  {} {}", "✓".green(), tool);
+        }
+
         println!(
             "{} Shims regenerated in {}",
             "✓".green(),
@@ -502,12 +2060,22 @@ impl GoVM {
         Ok(())
     }
 
-    /// Prune old versions
-    pub fn prune_versions(&self, keep: usize) -> Result<()> {
+    /// Prune old versions, keeping the global version and any version
+    /// referenced by a recorded or scanned project.
+    ///
+    /// With `unused_for`, versions are kept by recent use instead of by count.
+    pub fn prune_versions(
+        &self,
+        keep: usize,
+        unused_for: Option<Duration>,
+        scan: &[PathBuf],
+        yes: bool,
+        dry_run: bool,
+    ) -> Result<()> {
         let versions = self.get_installed_versions()?;
         let global = self.get_global_version()?;
 
-        if versions.len() <= keep {
+        if unused_for.is_none() && versions.len() <= keep {
             println!(
                 "{} Nothing to prune. {} versions installed, keeping {}.",
                 "→".blue(),
@@ -517,39 +2085,210 @@ impl GoVM {
             return Ok(());
         }
 
-        let to_remove: Vec<_> = versions
-            .iter()
-            .skip(keep)
-            .filter(|v| global.as_ref() != Some(*v))
+        // A reference like "1.22" (go.mod) protects the installed version it resolves to
+        let references: Vec<_> = projects::references(&self.projects_file, scan)?
+            .into_iter()
+            .map(|r| (self.installed_match(&r.version), r))
             .collect();
 
+        let mut kept = Vec::new();
+        let mut to_remove = Vec::new();
+        for (i, v) in versions.iter().enumerate() {
+            let mut reasons = Vec::new();
+            match unused_for {
+                Some(max_age) => {
+                    let (activity, age) = self.last_activity(v).unwrap_or(("used", Duration::ZERO));
+                    if age < max_age {
+                        reasons.push(format!("{} {}", activity, usage::format_age(age)));
+                    }
+                }
+                None if i < keep => reasons.push(format!("one of {} latest", keep)),
+                None => {}
+            }
+            if global.as_ref() == Some(v) {
+                reasons.push("global".to_string());
+            }
+            if self.linked_path(v).is_some() {
+                reasons.push("linked".to_string());
+            }
+            for (_, r) in references.iter().filter(|(m, _)| m.as_ref() == Some(v)) {
+                reasons.push(format!("used by {}", r.file.display()));
+            }
+
+            if reasons.is_empty() {
+                to_remove.push(v);
+            } else {
+                kept.push((v, reasons));
+            }
+        }
+
+        println!("{}", "Keeping:".bold());
+        for (v, reasons) in &kept {
+            println!(
+                "  - {} {}",
+                v.green(),
+                format!("({})", reasons.join(", ")).dimmed()
+            );
+        }
+        println!();
+
         if to_remove.is_empty() {
             println!("{} Nothing to prune.", "→".blue());
             return Ok(());
         }
 
+        let sizes: Vec<u64> = to_remove
+            .iter()
+            .map(|v| disk::dir_size(&self.versions_dir.join(v)))
+            .collect();
+        let total: u64 = sizes.iter().sum();
+
         println!("{}", "The following versions will be removed:".bold());
-        for v in &to_remove {
-            println!("  - {}", v.red());
+        for (v, size) in to_remove.iter().zip(&sizes) {
+            println!(
+                "  - {} {}",
+                v.red(),
+                format!("({})", HumanBytes(*size)).dimmed()
+            );
         }
         println!();
 
-        print!("Continue? [y/N] ");
-        io::stdout().flush()?;
+        if dry_run {
+            println!(
+                "{} Dry run: would reclaim {}",
+                "→".blue(),
+                HumanBytes(total)
+            );
+            return Ok(());
+        }
+
+        if !yes {
+            if !io::stdin().is_terminal() {
+                return Err(GovmError::ConfirmationRequired("prune"));
+            }
+
+            print!("Continue? [y/N] ");
+            io::stdout().flush()?;
 
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
 
-        if input.trim().to_lowercase() == "y" {
-            for v in to_remove {
-                let version_dir = self.versions_dir.join(v);
-                fs::remove_dir_all(&version_dir)?;
-                println!("{} Removed Go {}", "✓".green(), v);
+            if input.trim().to_lowercase() != "y" {
+                println!("{} Prune cancelled", "→".blue());
+                return Ok(());
             }
-        } else {
-            println!("{} Prune cancelled", "→".blue());
         }
 
+        for (v, size) in to_remove.iter().zip(&sizes) {
+            let version_dir = self.versions_dir.join(v);
+            self.pre_hook(Event::Uninstall, v)?;
+            fs::remove_dir_all(&version_dir)?;
+            self.remove_workspace(v)?;
+            usage::clear(&self.usage_dir, v)?;
+            manifest::clear(&self.manifests_dir, v)?;
+            println!(
+                "{} Removed Go {} {}",
+                "✓".green(),
+                v,
+                format!("({})", HumanBytes(*size)).dimmed()
+            );
+            self.post_hook(Event::Uninstall, v);
+        }
+        println!("{} Reclaimed {}", "✓".green(), HumanBytes(total));
+
         Ok(())
     }
 }
+
+/// A config value as the user would type it (strings unquoted)
+fn display_value(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Look up the binary archive of `version` for os/arch in the index
+fn find_archive(versions: &[GoVersion], version: &str, os: &str, arch: &str) -> Result<GoFile> {
+    let go_version = versions
+        .iter()
+        .find(|v| normalize(&v.version) == version)
+        .ok_or_else(|| GovmError::VersionNotFound(version.to_string()))?;
+
+    let file = go_version
+        .files
+        .iter()
+        .find(|f| f.os == os && f.arch == arch && f.kind == "archive")
+        .ok_or_else(|| GovmError::NoBinaryForPlatform {
+            version: version.to_string(),
+            os: os.to_string(),
+            arch: arch.to_string(),
+            available: go_version
+                .files
+                .iter()
+                .filter(|f| f.kind == "archive")
+                .map(|f| format!("{}/{}", f.os, f.arch))
+                .collect(),
+        })?;
+    ensure_tarball(file, version)?;
+    Ok(file.clone())
+}
+
+/// Only tarballs can be unpacked; refuse others before downloading them
+fn ensure_tarball(file: &GoFile, version: &str) -> Result<()> {
+    if !file.filename.ends_with(".tar.gz") {
+        return Err(GovmError::UnsupportedArchive {
+            version: version.to_string(),
+            os: file.os.clone(),
+            arch: file.arch.clone(),
+            filename: file.filename.clone(),
+        });
+    }
+    Ok(())
+}
+
+/// The checksum an archive must have under a project's govm.lock
+fn locked_checksum(path: &Path, lock: &Lock, file: &GoFile) -> Result<String> {
+    let expected = lock.checksum(&file.os, &file.arch).ok_or_else(|| {
+        GovmError::Lock(format!(
+            "{} has no checksum for {}/{}",
+            path.display(),
+            file.os,
+            file.arch
+        ))
+    })?;
+    // The index and the lock must agree before anything is downloaded
+    if !file.sha256.is_empty() && !file.sha256.eq_ignore_ascii_case(expected) {
+        return Err(GovmError::ChecksumMismatch {
+            file: file.filename.clone(),
+            expected: expected.to_string(),
+            actual: file.sha256.clone(),
+        });
+    }
+    Ok(expected.to_string())
+}
+
+/// The release `version` names on the command line: itself if the index has it,
+/// or else for a minor version like "1.22" the newest 1.22.x release
+fn resolve_release(versions: &[GoVersion], version: &str) -> String {
+    if versions.iter().any(|v| normalize(&v.version) == version) {
+        return version.to_string();
+    }
+    release_for(versions, version).unwrap_or_else(|| version.to_string())
+}
+
+/// The release `version` means in the index: itself, or for a minor version
+/// like "1.22" (as in go.mod) the newest 1.22.x release
+fn release_for(versions: &[GoVersion], version: &str) -> Option<String> {
+    let releases = versions.iter().map(|v| normalize(&v.version));
+    if version.matches('.').count() != 1 {
+        return releases.into_iter().find(|v| v == version);
+    }
+    let prefix = format!("{}.", version);
+    releases
+        .filter(|v| v == version || (v.starts_with(&prefix) && parse(v).3.is_empty()))
+        .max_by_key(|v| {
+            let (major, minor, patch, _) = parse(v);
+            (major, minor, patch)
+        })
+}
@@ -0,0 +1,168 @@
+use colored::*;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{GovmError, Result};
+
+/// An operation hooks can run around
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    Install,
+    Uninstall,
+    Use,
+}
+
+impl Event {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Event::Install => "install",
+            Event::Uninstall => "uninstall",
+            Event::Use => "use",
+        }
+    }
+}
+
+/// The toolchain a hook runs for
+pub struct Toolchain<'a> {
+    /// Version name as govm knows it, e.g. "1.22.3" or "1.22.3-linux-arm64"
+    pub version: &'a str,
+    pub goroot: &'a Path,
+    pub os: &'a str,
+    pub arch: &'a str,
+}
+
+/// Path of the hook script for `stage` ("pre" or "post") and `event`
+pub fn path(hooks_dir: &Path, stage: &str, event: Event) -> PathBuf {
+    hooks_dir.join(format!("{}-{}", stage, event.name()))
+}
+
+/// Run the pre-hook for `event`, if there is one. A failing pre-hook aborts the operation.
+pub fn run_pre(hooks_dir: &Path, event: Event, toolchain: &Toolchain) -> Result<()> {
+    run(hooks_dir, "pre", event, toolchain)
+}
+
+/// Run the post-hook for `event`, if there is one.
+///
+/// The operation already happened, so a failure is reported but not returned.
+pub fn run_post(hooks_dir: &Path, event: Event, toolchain: &Toolchain) {
+    if let Err(e) = run(hooks_dir, "post", event, toolchain) {
+        println!("{} {}", "⚠".yellow(), e);
+    }
+}
+
+fn run(hooks_dir: &Path, stage: &str, event: Event, toolchain: &Toolchain) -> Result<()> {
+    let hook = path(hooks_dir, stage, event);
+    if !hook.is_file() {
+        return Ok(());
+    }
+    let name = format!("{}-{}", stage, event.name());
+    if !is_executable(&hook) {
+        println!(
+            "{} Hook {} is not executable, skipped {}",
+            "⚠".yellow(),
+            hook.display(),
+            format!("(chmod +x {})", hook.display()).dimmed()
+        );
+        return Ok(());
+    }
+
+    let status = Command::new(&hook)
+        .env("GOVM_HOOK", &name)
+        .env("GOVM_HOOK_VERSION", toolchain.version)
+        .env("GOVM_HOOK_GOROOT", toolchain.goroot)
+        .env(
+            "GOVM_HOOK_PLATFORM",
+            format!("{}/{}", toolchain.os, toolchain.arch),
+        )
+        .status()
+        .map_err(|e| GovmError::HookFailed {
+            hook: name.clone(),
+            reason: e.to_string(),
+        })?;
+    if !status.success() {
+        return Err(GovmError::HookFailed {
+            hook: name,
+            reason: status.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).is_ok_and(|m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    fs::metadata(path).is_ok()
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tempfile::TempDir;
+
+    fn write_hook(dir: &Path, name: &str, script: &str) {
+        let hook = dir.join(name);
+        fs::write(&hook, script).unwrap();
+        fs::set_permissions(&hook, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    fn toolchain(goroot: &Path) -> Toolchain<'_> {
+        Toolchain {
+            version: "1.22.3",
+            goroot,
+            os: "linux",
+            arch: "amd64",
+        }
+    }
+
+    #[test]
+    fn test_missing_hook_is_fine() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(run_pre(temp_dir.path(), Event::Install, &toolchain(temp_dir.path())).is_ok());
+    }
+
+    #[test]
+    fn test_hook_gets_toolchain_env() {
+        let temp_dir = TempDir::new().unwrap();
+        let out = temp_dir.path().join("out");
+        write_hook(
+            temp_dir.path(),
+            "pre-use",
+            &format!(
+                "#!/bin/sh\necho \"$GOVM_HOOK $GOVM_HOOK_VERSION $GOVM_HOOK_GOROOT $GOVM_HOOK_PLATFORM\" > {}\n",
+                out.display()
+            ),
+        );
+        let goroot = Path::new("/opt/go");
+        run_pre(temp_dir.path(), Event::Use, &toolchain(goroot)).unwrap();
+        assert_eq!(
+            fs::read_to_string(&out).unwrap(),
+            "pre-use 1.22.3 /opt/go linux/amd64\n"
+        );
+    }
+
+    #[test]
+    fn test_failing_pre_hook_is_an_error() {
+        let temp_dir = TempDir::new().unwrap();
+        write_hook(temp_dir.path(), "pre-uninstall", "#!/bin/sh\nexit 3\n");
+        let result = run_pre(
+            temp_dir.path(),
+            Event::Uninstall,
+            &toolchain(temp_dir.path()),
+        );
+        assert!(matches!(result, Err(GovmError::HookFailed { .. })));
+    }
+
+    #[test]
+    fn test_non_executable_hook_is_skipped() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("pre-install"), "#!/bin/sh\nexit 1\n").unwrap();
+        assert!(run_pre(temp_dir.path(), Event::Install, &toolchain(temp_dir.path())).is_ok());
+    }
+}
@@ -0,0 +1,144 @@
+use reqwest::{Certificate, Client};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::config::{Config, NetworkConfig};
+use crate::constants::USER_AGENT;
+use crate::error::Result;
+
+/// Default time allowed to establish a connection
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default time allowed between two reads of a response
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(60);
+
+static CONFIG: OnceLock<HttpConfig> = OnceLock::new();
+static CLIENT: OnceLock<Client> = OnceLock::new();
+
+/// Settings for the shared HTTP client
+#[derive(Debug, Clone, PartialEq)]
+pub struct HttpConfig {
+    pub connect_timeout: Duration,
+    pub read_timeout: Duration,
+    /// Extra PEM bundle trusted in addition to the system roots
+    pub ca_bundle: Option<PathBuf>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            ca_bundle: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Read settings from `GOVM_CA_BUNDLE`, `GOVM_CONNECT_TIMEOUT` and
+    /// `GOVM_READ_TIMEOUT` (seconds), falling back to defaults
+    pub fn from_env() -> Self {
+        Self::from_settings(&Config::from_env().network)
+    }
+
+    /// Settings from the `[network]` section, falling back to defaults
+    pub fn from_settings(network: &NetworkConfig) -> Self {
+        let defaults = Self::default();
+        Self {
+            connect_timeout: network
+                .connect_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.connect_timeout),
+            read_timeout: network
+                .read_timeout
+                .map(Duration::from_secs)
+                .unwrap_or(defaults.read_timeout),
+            ca_bundle: network
+                .ca_bundle
+                .clone()
+                .filter(|p| !p.as_os_str().is_empty()),
+        }
+    }
+
+    /// Build a client. Proxies come from HTTP(S)_PROXY / ALL_PROXY, honoring NO_PROXY.
+    pub fn build(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(USER_AGENT)
+            .connect_timeout(self.connect_timeout);
+
+        if let Some(bundle) = &self.ca_bundle {
+            for cert in Certificate::from_pem_bundle(&fs::read(bundle)?)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        Ok(builder.build()?)
+    }
+}
+
+/// Settings for the process-wide client, read from the environment on first use
+/// unless `init` set them earlier
+pub fn config() -> &'static HttpConfig {
+    CONFIG.get_or_init(HttpConfig::from_env)
+}
+
+/// Set the process-wide client settings. Has no effect once the client is in use.
+pub fn init(config: HttpConfig) {
+    let _ = CONFIG.set(config);
+}
+
+/// The process-wide HTTP client, shared by every request govm makes
+pub fn client() -> Result<&'static Client> {
+    if let Some(client) = CLIENT.get() {
+        return Ok(client);
+    }
+    let client = config().build()?;
+    Ok(CLIENT.get_or_init(|| client))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_default_config() {
+        let config = HttpConfig::default();
+        assert_eq!(config.connect_timeout, Duration::from_secs(30));
+        assert_eq!(config.read_timeout, Duration::from_secs(60));
+        assert!(config.ca_bundle.is_none());
+    }
+
+    #[test]
+    fn test_build_with_missing_ca_bundle_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = HttpConfig {
+            ca_bundle: Some(temp_dir.path().join("missing.pem")),
+            ..HttpConfig::default()
+        };
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    fn test_build_with_invalid_ca_bundle_fails() {
+        let temp_dir = TempDir::new().unwrap();
+        let bundle = temp_dir.path().join("bad.pem");
+        fs::write(
+            &bundle,
+            "-----BEGIN CERTIFICATE-----\nnot base64\n-----END CERTIFICATE-----\n",
+        )
+        .unwrap();
+        let config = HttpConfig {
+            ca_bundle: Some(bundle),
+            ..HttpConfig::default()
+        };
+        assert!(config.build().is_err());
+    }
+
+    #[test]
+    fn test_user_agent_uses_crate_version() {
+        assert_eq!(USER_AGENT, format!("govm/{}", env!("CARGO_PKG_VERSION")));
+    }
+}
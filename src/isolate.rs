@@ -0,0 +1,111 @@
+use std::env;
+use std::ffi::OsString;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How much of the Go workspace is kept separate per Go version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Isolation {
+    /// Share the user's GOPATH and GOBIN (Go's defaults)
+    Off,
+    /// `go install` puts binaries in a per-version GOBIN
+    Gobin,
+    /// Per-version GOPATH (and so GOBIN), with the module cache still shared
+    Gopath,
+}
+
+impl Isolation {
+    /// Parse the `install.isolate` setting ("gobin" or "gopath"); anything else is off
+    pub fn parse(mode: Option<&str>) -> Self {
+        match mode.map(str::trim) {
+            Some("gobin") => Isolation::Gobin,
+            Some("gopath") => Isolation::Gopath,
+            _ => Isolation::Off,
+        }
+    }
+
+    /// Environment for a go command run with the per-version `workspace`
+    pub fn env_vars(&self, workspace: &Path) -> Vec<(&'static str, OsString)> {
+        match self {
+            Isolation::Off => Vec::new(),
+            Isolation::Gobin => vec![("GOBIN", workspace.join("bin").into())],
+            Isolation::Gopath => {
+                let mut vars = vec![
+                    ("GOPATH", workspace.as_os_str().to_owned()),
+                    ("GOBIN", workspace.join("bin").into()),
+                ];
+                // Keep downloading modules once, into the cache the user already has
+                if env::var_os("GOMODCACHE").is_none() {
+                    if let Some(cache) = shared_mod_cache() {
+                        vars.push(("GOMODCACHE", cache.into()));
+                    }
+                }
+                vars
+            }
+        }
+    }
+}
+
+/// The module cache Go would use without isolation: $GOPATH/pkg/mod, or ~/go/pkg/mod
+fn shared_mod_cache() -> Option<PathBuf> {
+    let gopath = env::var_os("GOPATH")
+        .and_then(|p| env::split_paths(&p).next())
+        .filter(|p| !p.as_os_str().is_empty())
+        .or_else(|| dirs::home_dir().map(|home| home.join("go")))?;
+    Some(gopath.join("pkg").join("mod"))
+}
+
+/// Names of the binaries in a per-version GOBIN
+pub fn installed_binaries(workspace: &Path) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(workspace.join("bin")) else {
+        return Vec::new();
+    };
+    let mut names: Vec<String> = entries
+        .flatten()
+        .filter(|e| e.path().is_file())
+        .filter_map(|e| e.file_name().to_str().map(str::to_string))
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_env_vars_per_mode() {
+        let workspace = Path::new("/home/u/.govm/gopath/1.22.0");
+
+        assert!(Isolation::Off.env_vars(workspace).is_empty());
+
+        let gobin = Isolation::Gobin.env_vars(workspace);
+        assert_eq!(gobin, [("GOBIN", workspace.join("bin").into())]);
+
+        let gopath = Isolation::Gopath.env_vars(workspace);
+        assert!(gopath.contains(&("GOPATH", workspace.as_os_str().to_owned())));
+        assert!(gopath.contains(&("GOBIN", workspace.join("bin").into())));
+        // GOMODCACHE never points into the per-version workspace
+        for (name, value) in &gopath {
+            if *name == "GOMODCACHE" {
+                assert!(!Path::new(value).starts_with(workspace));
+            }
+        }
+    }
+
+    #[test]
+    fn test_installed_binaries() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(installed_binaries(temp_dir.path()).is_empty());
+
+        let bin = temp_dir.path().join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("staticcheck"), "").unwrap();
+        fs::write(bin.join("gopls"), "").unwrap();
+        assert_eq!(
+            installed_binaries(temp_dir.path()),
+            ["gopls", "staticcheck"]
+        );
+    }
+}
@@ -0,0 +1,32 @@
+//! govm - Go Version Manager
+//!
+//! Library behind the `govm` CLI. Use [`GoVM`] to install, select and run Go
+//! versions, [`version::resolve`] to find the version a directory uses, and
+//! the [`download`] functions to fetch the remote index and archives. Where
+//! versions and archives come from is pluggable via [`source`].
+
+pub mod auth;
+mod build;
+pub mod config;
+mod constants;
+mod disk;
+pub mod download;
+pub mod error;
+mod govm;
+mod hooks;
+pub mod http;
+mod isolate;
+mod lock;
+mod manifest;
+pub mod migrate;
+mod projects;
+mod shim;
+pub mod source;
+mod support;
+mod tools;
+pub mod types;
+pub mod usage;
+pub mod version;
+
+pub use error::GovmError;
+pub use govm::GoVM;
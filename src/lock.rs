@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{GovmError, Result};
+use crate::types::GoVersion;
+use crate::version::normalize;
+
+/// Project file pinning a Go version and the checksums of its release archives
+pub const LOCK_FILE: &str = "govm.lock";
+
+const HEADER: &str = "# Written by `govm lock`. Pins the Go toolchain of this project;\n\
+                      # commit it and run `govm lock` again to upgrade.\n\n";
+
+/// The contents of a govm.lock file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Lock {
+    pub version: String,
+    /// Archive sha256 per platform, keyed by "os/arch"
+    pub sha256: BTreeMap<String, String>,
+}
+
+impl Lock {
+    /// Lock a release at the checksums the version index publishes
+    pub fn from_release(release: &GoVersion) -> Self {
+        let sha256 = release
+            .files
+            .iter()
+            .filter(|f| f.kind == "archive" && !f.sha256.is_empty())
+            .map(|f| (format!("{}/{}", f.os, f.arch), f.sha256.clone()))
+            .collect();
+        Self {
+            version: normalize(&release.version),
+            sha256,
+        }
+    }
+
+    /// The locked archive checksum for a platform
+    pub fn checksum(&self, os: &str, arch: &str) -> Option<&str> {
+        self.sha256
+            .get(&format!("{}/{}", os, arch))
+            .map(String::as_str)
+    }
+
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)?;
+        let lock: Lock = toml::from_str(&content)
+            .map_err(|e| GovmError::Lock(format!("{}: {}", path.display(), e)))?;
+        Ok(Lock {
+            version: normalize(&lock.version),
+            ..lock
+        })
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        let body = toml::to_string(self).map_err(|e| GovmError::Lock(e.to_string()))?;
+        fs::write(path, format!("{}{}", HEADER, body))?;
+        Ok(())
+    }
+}
+
+/// The nearest govm.lock in `dir` or its parents
+pub fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|d| d.join(LOCK_FILE))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::GoFile;
+    use tempfile::TempDir;
+
+    fn file(os: &str, arch: &str, kind: &str, sha256: &str) -> GoFile {
+        GoFile {
+            filename: format!("go1.22.3.{}-{}.tar.gz", os, arch),
+            os: os.to_string(),
+            arch: arch.to_string(),
+            sha256: sha256.to_string(),
+            size: 1,
+            kind: kind.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_from_release_keeps_archive_checksums() {
+        let release = GoVersion {
+            version: "go1.22.3".to_string(),
+            stable: true,
+            files: vec![
+                file("linux", "amd64", "archive", "aaa"),
+                file("darwin", "arm64", "archive", "bbb"),
+                file("", "", "source", "ccc"),
+                file("windows", "amd64", "installer", "ddd"),
+            ],
+        };
+        let lock = Lock::from_release(&release);
+        assert_eq!(lock.version, "1.22.3");
+        assert_eq!(lock.sha256.len(), 2);
+        assert_eq!(lock.checksum("linux", "amd64"), Some("aaa"));
+        assert_eq!(lock.checksum("plan9", "arm"), None);
+    }
+
+    #[test]
+    fn test_write_read_and_find() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("cmd/app");
+        fs::create_dir_all(&nested).unwrap();
+        let lock = Lock {
+            version: "1.22.3".to_string(),
+            sha256: BTreeMap::from([("linux/amd64".to_string(), "aaa".to_string())]),
+        };
+        let path = temp_dir.path().join(LOCK_FILE);
+        lock.write(&path).unwrap();
+
+        let content = fs::read_to_string(&path).unwrap();
+        assert!(content.starts_with("# Written by `govm lock`"));
+        assert!(content.contains("\"linux/amd64\" = \"aaa\""), "{}", content);
+        assert_eq!(Lock::read(&path).unwrap(), lock);
+        assert_eq!(find(&nested), Some(path));
+    }
+
+    #[test]
+    fn test_read_rejects_invalid_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(LOCK_FILE);
+        fs::write(&path, "version = \"1.22.3\"\nsha = {}\n").unwrap();
+        assert!(matches!(Lock::read(&path), Err(GovmError::Lock(_))));
+    }
+}
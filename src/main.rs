@@ -4,29 +4,70 @@
 //! Inspired by rbenv, pyenv, and nvm.
 
 mod cli;
-mod constants;
-mod download;
-mod govm;
-mod shim;
-mod types;
-mod version;
 
-use anyhow::Result;
-use clap::Parser;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
 use colored::*;
+use std::process::exit;
 
-use cli::{Cli, Commands};
-use govm::GoVM;
+use cli::{Cli, Commands, ConfigCommand, ToolsCommand};
+use govm::download::get_platform;
+use govm::{GoVM, GovmError};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() {
     let cli = Cli::parse();
+
+    if let Err(err) = run(cli).await {
+        eprintln!("{} {}", "✗".red(), err);
+        exit(err.exit_code());
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), GovmError> {
     let govm = GoVM::new()?;
 
-    match cli.command {
-        Commands::Install { go_version } => {
-            govm.install_version(&go_version).await?;
+    // NO_COLOR still wins over ui.color
+    if let Some(color) = govm.config.ui.color {
+        if std::env::var_os("NO_COLOR").is_none() {
+            colored::control::set_override(color);
         }
+    }
+
+    match cli.command {
+        Commands::Install {
+            go_versions,
+            os,
+            arch,
+            source,
+            all_projects,
+        } => match (go_versions.as_slice(), all_projects) {
+            (_, Some(dir)) => govm.install_all_projects(&dir).await?,
+            ([], None) => govm.install_project_version().await?,
+            ([go_version], None) => {
+                if source {
+                    govm.install_from_source(go_version).await?;
+                } else if os.is_some() || arch.is_some() {
+                    let (host_os, host_arch) = get_platform();
+                    let os = os.as_deref().unwrap_or(host_os);
+                    let arch = arch.as_deref().unwrap_or(host_arch);
+                    govm.install_version_for(go_version, os, arch).await?;
+                } else {
+                    govm.install_version(go_version).await?;
+                }
+            }
+            (go_versions, None) => {
+                if source || os.is_some() || arch.is_some() {
+                    Cli::command()
+                        .error(
+                            ErrorKind::ArgumentConflict,
+                            "--source, --os and --arch take a single VERSION",
+                        )
+                        .exit();
+                }
+                govm.install_versions(go_versions).await?;
+            }
+        },
         Commands::Use { go_version, local } => {
             govm.use_version(&go_version, local).await?;
         }
@@ -43,8 +84,11 @@ async fn main() -> Result<()> {
         Commands::Version => {
             govm.show_version()?;
         }
-        Commands::Versions => {
-            govm.list_versions()?;
+        Commands::Versions { verbose } => {
+            govm.list_versions(verbose)?;
+        }
+        Commands::Du => {
+            govm.disk_usage()?;
         }
         Commands::ListRemote { all, limit } => {
             govm.list_remote_versions(all, limit).await?;
@@ -52,18 +96,79 @@ async fn main() -> Result<()> {
         Commands::Uninstall { go_version } => {
             govm.uninstall_version(&go_version)?;
         }
+        Commands::Verify { go_version } => {
+            govm.verify_versions(go_version.as_deref())?;
+        }
+        Commands::Reinstall { go_version } => {
+            govm.reinstall_version(&go_version).await?;
+        }
+        Commands::Lock { go_version } => {
+            govm.lock_version(go_version.as_deref()).await?;
+        }
+        Commands::Link { name, path } => {
+            govm.link_version(&name, &path)?;
+        }
+        Commands::Migrate { from, link, scan } => {
+            let scan = if scan.is_empty() {
+                vec![std::env::current_dir()?]
+            } else {
+                scan
+            };
+            govm.migrate(from, link, &scan)?;
+        }
         Commands::Which { command } => {
             govm.which_command(&command)?;
         }
-        Commands::Exec { command, args } => {
-            govm.exec_command(&command, &args)?;
+        Commands::Exec {
+            platform,
+            command,
+            args,
+        } => {
+            govm.exec_command(&command, &args, platform)?;
         }
         Commands::Rehash => {
             govm.rehash()?;
         }
-        Commands::Prune { keep } => {
-            govm.prune_versions(keep)?;
+        Commands::Prune {
+            keep,
+            unused_for,
+            scan,
+            yes,
+            dry_run,
+        } => {
+            // An explicit --keep wins over a configured unused_for
+            let unused_for = match (unused_for, keep, &govm.config.prune.unused_for) {
+                (None, None, Some(age)) => Some(govm::usage::parse_age(age)?),
+                (unused_for, _, _) => unused_for,
+            };
+            let keep = keep.unwrap_or(govm.config.prune.keep);
+            govm.prune_versions(keep, unused_for, &scan, yes, dry_run)?;
         }
+        Commands::Tools { command } => match command {
+            ToolsCommand::Install => {
+                govm.install_project_tools()?;
+            }
+            ToolsCommand::List => {
+                govm.list_project_tools()?;
+            }
+            ToolsCommand::Reinstall { go_version } => {
+                govm.reinstall_default_tools(&go_version)?;
+            }
+        },
+        Commands::Config { command } => match command {
+            ConfigCommand::Get { key } => {
+                govm.get_config(&key)?;
+            }
+            ConfigCommand::Set { key, value } => {
+                govm.set_config(&key, &value)?;
+            }
+            ConfigCommand::Unset { key } => {
+                govm.unset_config(&key)?;
+            }
+            ConfigCommand::List => {
+                govm.list_config()?;
+            }
+        },
     }
 
     Ok(())
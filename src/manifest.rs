@@ -0,0 +1,217 @@
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+
+/// File hashes of an installed toolchain: path relative to GOROOT ('/'-separated) to hex sha256
+pub type Manifest = BTreeMap<String, String>;
+
+/// Differences between a GOROOT and its manifest
+#[derive(Debug, Default, PartialEq)]
+pub struct Report {
+    /// Files whose content changed
+    pub modified: Vec<String>,
+    /// Files that were deleted
+    pub missing: Vec<String>,
+    /// Files that weren't there at install time (reported, but not an error)
+    pub added: Vec<String>,
+}
+
+impl Report {
+    /// Whether every recorded file is intact
+    pub fn is_ok(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Hex sha256 of a file's content
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha256::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// Hash every file under `goroot`. Symlinks are recorded by their target, not followed.
+pub fn hash_tree(goroot: &Path) -> Result<Manifest> {
+    let mut manifest = Manifest::new();
+    let mut dirs = vec![goroot.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let file_type = fs::symlink_metadata(&path)?.file_type();
+            let hash = if file_type.is_dir() {
+                dirs.push(path);
+                continue;
+            } else if file_type.is_symlink() {
+                let target = fs::read_link(&path)?;
+                format!("{:x}", Sha256::digest(target.to_string_lossy().as_bytes()))
+            } else {
+                hash_file(&path)?
+            };
+            manifest.insert(relative_name(goroot, &path), hash);
+        }
+    }
+    Ok(manifest)
+}
+
+fn relative_name(goroot: &Path, path: &Path) -> String {
+    let relative = path.strip_prefix(goroot).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Save a manifest in `sha256sum` format
+pub fn write(path: &Path, manifest: &Manifest) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let content: String = manifest
+        .iter()
+        .map(|(name, hash)| format!("{}  {}\n", hash, name))
+        .collect();
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// Load a manifest saved by `write`; `None` if none was recorded
+pub fn read(path: &Path) -> Result<Option<Manifest>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    content
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            line.split_once("  ")
+                .map(|(hash, name)| (name.to_string(), hash.to_string()))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("Malformed manifest {}", path.display()),
+                    )
+                    .into()
+                })
+        })
+        .collect::<Result<Manifest>>()
+        .map(Some)
+}
+
+/// Compare a GOROOT against its manifest
+pub fn check(goroot: &Path, manifest: &Manifest) -> Result<Report> {
+    let current = hash_tree(goroot)?;
+    let mut report = Report::default();
+    for (name, hash) in manifest {
+        match current.get(name) {
+            None => report.missing.push(name.clone()),
+            Some(h) if h != hash => report.modified.push(name.clone()),
+            Some(_) => {}
+        }
+    }
+    report.added = current
+        .keys()
+        .filter(|name| !manifest.contains_key(*name))
+        .cloned()
+        .collect();
+    Ok(report)
+}
+
+/// Remember the sha256 of the archive a version was installed from
+pub fn write_archive_checksum(manifests_dir: &Path, version: &str, sha256: &str) -> Result<()> {
+    fs::create_dir_all(manifests_dir)?;
+    fs::write(
+        archive_checksum_path(manifests_dir, version),
+        format!("{}\n", sha256),
+    )?;
+    Ok(())
+}
+
+/// The sha256 of the archive a version was installed from, if it was recorded
+pub fn read_archive_checksum(manifests_dir: &Path, version: &str) -> Option<String> {
+    let content = fs::read_to_string(archive_checksum_path(manifests_dir, version)).ok()?;
+    Some(content.trim().to_string()).filter(|s| !s.is_empty())
+}
+
+fn archive_checksum_path(manifests_dir: &Path, version: &str) -> PathBuf {
+    manifests_dir.join(format!("{}.archive", version))
+}
+
+/// Forget a version's manifest and archive checksum (on uninstall)
+pub fn clear(manifests_dir: &Path, version: &str) -> Result<()> {
+    for path in [
+        manifests_dir.join(version),
+        archive_checksum_path(manifests_dir, version),
+    ] {
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_goroot(dir: &Path) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::create_dir_all(dir.join("src/fmt")).unwrap();
+        fs::write(dir.join("bin/go"), "go binary").unwrap();
+        fs::write(dir.join("VERSION"), "go1.22.3\n").unwrap();
+        fs::write(dir.join("src/fmt/print.go"), "package fmt\n").unwrap();
+    }
+
+    #[test]
+    fn test_hash_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join("f");
+        fs::write(&path, "abc").unwrap();
+        assert_eq!(
+            hash_file(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_write_read_roundtrip() {
+        let temp_dir = TempDir::new().unwrap();
+        let goroot = temp_dir.path().join("go");
+        fake_goroot(&goroot);
+
+        let manifest = hash_tree(&goroot).unwrap();
+        let names: Vec<_> = manifest.keys().map(String::as_str).collect();
+        assert_eq!(names, ["VERSION", "bin/go", "src/fmt/print.go"]);
+
+        let path = temp_dir.path().join("manifests/1.22.3");
+        write(&path, &manifest).unwrap();
+        assert_eq!(read(&path).unwrap(), Some(manifest));
+        assert_eq!(read(&temp_dir.path().join("none")).unwrap(), None);
+    }
+
+    #[test]
+    fn test_check_reports_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let goroot = temp_dir.path().join("go");
+        fake_goroot(&goroot);
+        let manifest = hash_tree(&goroot).unwrap();
+        assert!(check(&goroot, &manifest).unwrap().is_ok());
+
+        fs::write(goroot.join("bin/go"), "truncated").unwrap();
+        fs::remove_file(goroot.join("src/fmt/print.go")).unwrap();
+        fs::write(goroot.join("extra"), "").unwrap();
+
+        let report = check(&goroot, &manifest).unwrap();
+        assert!(!report.is_ok());
+        assert_eq!(report.modified, ["bin/go"]);
+        assert_eq!(report.missing, ["src/fmt/print.go"]);
+        assert_eq!(report.added, ["extra"]);
+    }
+}
@@ -0,0 +1,281 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::projects::{self, Reference};
+use crate::version::normalize;
+
+/// Another Go version manager whose toolchains can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// goenv (~/.goenv)
+    Goenv,
+    /// gvm (~/.gvm)
+    Gvm,
+    /// asdf with the golang plugin (~/.asdf)
+    Asdf,
+    /// voidint/g (~/.g)
+    G,
+}
+
+/// Parse a tool name like "goenv" (for use as a clap value parser)
+pub fn parse_tool(s: &str) -> std::result::Result<Tool, String> {
+    match s {
+        "goenv" => Ok(Tool::Goenv),
+        "gvm" => Ok(Tool::Gvm),
+        "asdf" => Ok(Tool::Asdf),
+        "g" => Ok(Tool::G),
+        _ => Err(format!(
+            "Unknown tool '{}'. Use one of goenv, gvm, asdf, g",
+            s
+        )),
+    }
+}
+
+impl Tool {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Tool::Goenv => "goenv",
+            Tool::Gvm => "gvm",
+            Tool::Asdf => "asdf",
+            Tool::G => "g",
+        }
+    }
+
+    /// The tool's data directory, honoring the tool's own override variable
+    pub fn default_root(&self, home: &Path) -> PathBuf {
+        let (var, dir) = match self {
+            Tool::Goenv => ("GOENV_ROOT", ".goenv"),
+            Tool::Gvm => ("GVM_ROOT", ".gvm"),
+            Tool::Asdf => ("ASDF_DATA_DIR", ".asdf"),
+            Tool::G => ("G_HOME", ".g"),
+        };
+        env::var_os(var)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| home.join(dir))
+    }
+
+    /// Installed toolchains as (version, GOROOT), skipping anything without bin/go
+    pub fn toolchains(&self, root: &Path) -> Vec<(String, PathBuf)> {
+        let (versions_dir, goroot_subdir) = match self {
+            Tool::Goenv => (root.join("versions"), None),
+            Tool::Gvm => (root.join("gos"), None),
+            Tool::Asdf => (root.join("installs").join("golang"), Some("go")),
+            Tool::G => (root.join("versions"), Some("go")),
+        };
+
+        let Ok(entries) = fs::read_dir(&versions_dir) else {
+            return Vec::new();
+        };
+        let mut toolchains: Vec<(String, PathBuf)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_str()?.to_string();
+                let goroot = match goroot_subdir {
+                    Some(sub) => entry.path().join(sub),
+                    None => entry.path(),
+                };
+                goroot
+                    .join("bin")
+                    .join("go")
+                    .exists()
+                    .then(|| (normalize(&name), goroot))
+            })
+            .collect();
+        toolchains.sort();
+        toolchains
+    }
+
+    /// The tool's global version, if it has one
+    pub fn global_version(&self, root: &Path, home: &Path) -> Option<String> {
+        let version = match self {
+            Tool::Goenv => fs::read_to_string(root.join("version"))
+                .ok()?
+                .lines()
+                .next()?
+                .trim()
+                .to_string(),
+            // environments/default sets gvm_go_name="go1.21.0"
+            Tool::Gvm => {
+                let content = fs::read_to_string(root.join("environments").join("default")).ok()?;
+                let start = content.find("gvm_go_name=\"")? + "gvm_go_name=\"".len();
+                let rest = &content[start..];
+                rest[..rest.find('"')?].to_string()
+            }
+            Tool::Asdf => tool_versions_golang(&home.join(".tool-versions"))?,
+            // ~/.g/go links to versions/<version>/go
+            Tool::G => fs::read_link(root.join("go"))
+                .ok()?
+                .parent()?
+                .file_name()?
+                .to_str()?
+                .to_string(),
+        };
+        let version = normalize(&version);
+        (!version.is_empty() && version != "system").then_some(version)
+    }
+
+    /// Project files under `dir` that pin a version in a format govm doesn't read
+    pub fn project_files(&self, dir: &Path) -> Vec<Reference> {
+        let mut refs = Vec::new();
+        projects::walk(dir, &mut |dir| {
+            let found = match self {
+                // goenv uses .go-version, and g has no per-project files
+                Tool::Goenv | Tool::G => None,
+                Tool::Gvm => {
+                    let file = dir.join(".gvmrc");
+                    gvmrc_version(&file).map(|v| (v, file))
+                }
+                Tool::Asdf => {
+                    let file = dir.join(".tool-versions");
+                    tool_versions_golang(&file).map(|v| (v, file))
+                }
+            };
+            if let Some((version, file)) = found {
+                refs.push(Reference {
+                    version: normalize(&version),
+                    file,
+                });
+            }
+        });
+        refs
+    }
+}
+
+/// The golang entry of an asdf .tool-versions file
+fn tool_versions_golang(file: &Path) -> Option<String> {
+    let content = fs::read_to_string(file).ok()?;
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        (parts.next() == Some("golang"))
+            .then(|| parts.next().map(str::to_string))
+            .flatten()
+    })
+}
+
+/// The version a .gvmrc selects with `gvm use <version>`
+fn gvmrc_version(file: &Path) -> Option<String> {
+    let content = fs::read_to_string(file).ok()?;
+    content.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        (parts.next() == Some("gvm") && parts.next() == Some("use"))
+            .then(|| parts.next().map(str::to_string))
+            .flatten()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn fake_goroot(dir: &Path) {
+        fs::create_dir_all(dir.join("bin")).unwrap();
+        fs::write(dir.join("bin").join("go"), "").unwrap();
+    }
+
+    #[test]
+    fn test_parse_tool() {
+        assert_eq!(parse_tool("asdf"), Ok(Tool::Asdf));
+        assert!(parse_tool("nvm").is_err());
+    }
+
+    #[test]
+    fn test_goenv_toolchains_and_global() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fake_goroot(&root.join("versions/1.21.0"));
+        fake_goroot(&root.join("versions/1.22.1"));
+        fs::create_dir_all(root.join("versions/broken")).unwrap();
+        fs::write(root.join("version"), "1.22.1\n").unwrap();
+
+        let toolchains = Tool::Goenv.toolchains(root);
+        let versions: Vec<_> = toolchains.iter().map(|(v, _)| v.as_str()).collect();
+        assert_eq!(versions, ["1.21.0", "1.22.1"]);
+        assert_eq!(
+            Tool::Goenv.global_version(root, root),
+            Some("1.22.1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_gvm_toolchains_and_global() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fake_goroot(&root.join("gos/go1.21.0"));
+        fs::create_dir_all(root.join("environments")).unwrap();
+        fs::write(
+            root.join("environments/default"),
+            "export gvm_go_name; gvm_go_name=\"go1.21.0\"\n",
+        )
+        .unwrap();
+
+        let toolchains = Tool::Gvm.toolchains(root);
+        assert_eq!(
+            toolchains,
+            [("1.21.0".to_string(), root.join("gos/go1.21.0"))]
+        );
+        assert_eq!(
+            Tool::Gvm.global_version(root, root),
+            Some("1.21.0".to_string())
+        );
+    }
+
+    #[test]
+    fn test_asdf_toolchains_global_and_project_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path().join(".asdf");
+        fake_goroot(&root.join("installs/golang/1.22.0/go"));
+        fs::write(
+            temp_dir.path().join(".tool-versions"),
+            "nodejs 20.0.0\ngolang 1.22.0\n",
+        )
+        .unwrap();
+        let project = temp_dir.path().join("src/app");
+        fs::create_dir_all(&project).unwrap();
+        fs::write(project.join(".tool-versions"), "golang 1.21.5\n").unwrap();
+
+        let toolchains = Tool::Asdf.toolchains(&root);
+        assert_eq!(
+            toolchains,
+            [("1.22.0".to_string(), root.join("installs/golang/1.22.0/go"))]
+        );
+        assert_eq!(
+            Tool::Asdf.global_version(&root, temp_dir.path()),
+            Some("1.22.0".to_string())
+        );
+
+        let refs = Tool::Asdf.project_files(&temp_dir.path().join("src"));
+        assert_eq!(
+            refs,
+            [Reference {
+                version: "1.21.5".to_string(),
+                file: project.join(".tool-versions"),
+            }]
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_g_global_follows_link() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fake_goroot(&root.join("versions/1.20.3/go"));
+        std::os::unix::fs::symlink(root.join("versions/1.20.3/go"), root.join("go")).unwrap();
+
+        assert_eq!(Tool::G.toolchains(root).len(), 1);
+        assert_eq!(
+            Tool::G.global_version(root, root),
+            Some("1.20.3".to_string())
+        );
+    }
+
+    #[test]
+    fn test_gvmrc_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let file = temp_dir.path().join(".gvmrc");
+        fs::write(&file, "gvm use go1.19.4\n").unwrap();
+        assert_eq!(gvmrc_version(&file), Some("go1.19.4".to_string()));
+    }
+}
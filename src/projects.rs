@@ -0,0 +1,234 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::Result;
+use crate::lock::{Lock, LOCK_FILE};
+use crate::version::normalize;
+
+/// Directories never descended into when scanning for projects
+const SKIP_DIRS: &[&str] = &["node_modules", "vendor", "target"];
+
+/// A project file that pins a Go version
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reference {
+    pub version: String,
+    pub file: PathBuf,
+}
+
+/// Record a project directory in the registry (no-op if already recorded)
+pub fn record(projects_file: &Path, dir: &Path) -> Result<()> {
+    let mut dirs = load(projects_file)?;
+    if dirs.iter().any(|d| d == dir) {
+        return Ok(());
+    }
+    dirs.push(dir.to_path_buf());
+    save(projects_file, &dirs)
+}
+
+/// Load recorded project directories
+pub fn load(projects_file: &Path) -> Result<Vec<PathBuf>> {
+    if !projects_file.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(projects_file)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(PathBuf::from)
+        .collect())
+}
+
+fn save(projects_file: &Path, dirs: &[PathBuf]) -> Result<()> {
+    let content: String = dirs.iter().map(|d| format!("{}\n", d.display())).collect();
+    fs::write(projects_file, content)?;
+    Ok(())
+}
+
+/// Read the version a directory pins via .go-version, govm.lock or go.mod
+pub fn read_project_version(dir: &Path) -> Option<Reference> {
+    let version_file = dir.join(".go-version");
+    if let Ok(content) = fs::read_to_string(&version_file) {
+        let version = normalize(content.trim());
+        if !version.is_empty() {
+            return Some(Reference {
+                version,
+                file: version_file,
+            });
+        }
+    }
+
+    let lock_file = dir.join(LOCK_FILE);
+    if let Ok(lock) = Lock::read(&lock_file) {
+        return Some(Reference {
+            version: lock.version,
+            file: lock_file,
+        });
+    }
+
+    let go_mod = dir.join("go.mod");
+    let content = fs::read_to_string(&go_mod).ok()?;
+    let version = parse_go_mod(&content)?;
+    Some(Reference {
+        version,
+        file: go_mod,
+    })
+}
+
+/// Extract the Go version from go.mod, preferring the `toolchain` directive
+/// over the `go` directive
+pub fn parse_go_mod(content: &str) -> Option<String> {
+    let directive = |name: &str| {
+        content.lines().find_map(|line| {
+            let mut parts = line.split_whitespace();
+            if parts.next() == Some(name) {
+                parts.next().map(normalize)
+            } else {
+                None
+            }
+        })
+    };
+    directive("toolchain").or_else(|| directive("go"))
+}
+
+/// Collect references from recorded project directories and scanned trees.
+///
+/// Recorded directories that no longer pin a version are dropped from the registry.
+pub fn references(projects_file: &Path, scan_dirs: &[PathBuf]) -> Result<Vec<Reference>> {
+    let dirs = load(projects_file)?;
+    let mut live = Vec::new();
+    let mut refs = Vec::new();
+
+    for dir in &dirs {
+        if let Some(r) = read_project_version(dir) {
+            refs.push(r);
+            live.push(dir.clone());
+        }
+    }
+    if live.len() != dirs.len() {
+        save(projects_file, &live)?;
+    }
+
+    for dir in scan_dirs {
+        scan(dir, &mut refs);
+    }
+
+    Ok(refs)
+}
+
+/// Walk a directory tree collecting project references
+fn scan(dir: &Path, refs: &mut Vec<Reference>) {
+    walk(dir, &mut |dir| {
+        if let Some(r) = read_project_version(dir) {
+            if !refs.iter().any(|existing| existing.file == r.file) {
+                refs.push(r);
+            }
+        }
+    });
+}
+
+/// Call `visit` for `dir` and every directory below it, skipping hidden and dependency dirs
+pub fn walk(dir: &Path, visit: &mut dyn FnMut(&Path)) {
+    visit(dir);
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        if !file_type.is_dir() {
+            continue;
+        }
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref()) {
+            continue;
+        }
+        walk(&entry.path(), visit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_go_mod_toolchain() {
+        let content = "module example.com/foo\n\ngo 1.21\n\ntoolchain go1.22.3\n";
+        assert_eq!(parse_go_mod(content), Some("1.22.3".to_string()));
+    }
+
+    #[test]
+    fn test_parse_go_mod_go_directive() {
+        let content = "module example.com/foo\n\ngo 1.21.5\n";
+        assert_eq!(parse_go_mod(content), Some("1.21.5".to_string()));
+    }
+
+    #[test]
+    fn test_parse_go_mod_missing() {
+        assert_eq!(parse_go_mod("module example.com/foo\n"), None);
+    }
+
+    #[test]
+    fn test_record_deduplicates() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_file = temp_dir.path().join("projects");
+
+        record(&projects_file, Path::new("/a")).unwrap();
+        record(&projects_file, Path::new("/b")).unwrap();
+        record(&projects_file, Path::new("/a")).unwrap();
+
+        assert_eq!(
+            load(&projects_file).unwrap(),
+            vec![PathBuf::from("/a"), PathBuf::from("/b")]
+        );
+    }
+
+    #[test]
+    fn test_read_project_version_prefers_go_version_file() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(".go-version"), "1.21.0\n").unwrap();
+        fs::write(temp_dir.path().join("go.mod"), "go 1.22.0\n").unwrap();
+
+        let r = read_project_version(temp_dir.path()).unwrap();
+        assert_eq!(r.version, "1.21.0");
+        assert_eq!(r.file, temp_dir.path().join(".go-version"));
+    }
+
+    #[test]
+    fn test_references_drops_dead_projects() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_file = temp_dir.path().join("projects");
+        let live = temp_dir.path().join("live");
+        let dead = temp_dir.path().join("dead");
+        fs::create_dir_all(&live).unwrap();
+        fs::write(live.join(".go-version"), "1.21.0\n").unwrap();
+
+        record(&projects_file, &live).unwrap();
+        record(&projects_file, &dead).unwrap();
+
+        let refs = references(&projects_file, &[]).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].version, "1.21.0");
+        assert_eq!(load(&projects_file).unwrap(), vec![live]);
+    }
+
+    #[test]
+    fn test_references_scans_nested_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let projects_file = temp_dir.path().join("projects");
+        let nested = temp_dir.path().join("repos").join("svc");
+        let hidden = temp_dir.path().join(".cache").join("mod");
+        fs::create_dir_all(&nested).unwrap();
+        fs::create_dir_all(&hidden).unwrap();
+        fs::write(nested.join("go.mod"), "toolchain go1.22.1\n").unwrap();
+        fs::write(hidden.join(".go-version"), "1.19.0\n").unwrap();
+
+        let refs = references(&projects_file, &[temp_dir.path().to_path_buf()]).unwrap();
+        assert_eq!(refs.len(), 1);
+        assert_eq!(refs[0].version, "1.22.1");
+    }
+}
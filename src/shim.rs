@@ -1,17 +1,23 @@
-use anyhow::Result;
 use std::env;
 use std::fs;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
 
 use crate::constants::GO_BINARIES;
+use crate::error::Result;
 
 /// Ensure shims exist - only creates them if missing or outdated
 pub fn ensure_shims(shims_dir: &Path) -> Result<()> {
+    ensure_shims_for(shims_dir, GO_BINARIES)
+}
+
+/// Ensure shims exist for the given binaries (e.g. tools in a per-version GOBIN)
+pub fn ensure_shims_for<S: AsRef<str>>(shims_dir: &Path, binaries: &[S]) -> Result<()> {
     let govm_path = env::current_exe()?;
     let govm_path_str = govm_path.display().to_string();
 
-    for binary in GO_BINARIES {
+    for binary in binaries {
+        let binary = binary.as_ref();
         let shim_path = shims_dir.join(binary);
 
         // Check if shim exists and contains correct govm path
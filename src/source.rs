@@ -0,0 +1,229 @@
+use futures_util::future::BoxFuture;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+
+use crate::auth;
+use crate::config::{Config, MirrorConfig};
+use crate::constants::{GO_DOWNLOAD_BASE, GO_VERSION_LIST};
+use crate::download::{download_file, download_unpacked, fetch_versions_from, unpack_hashed};
+use crate::error::{GovmError, Result};
+use crate::types::{GoFile, GoVersion};
+
+/// Where the list of available Go versions comes from
+pub trait VersionSource: Send + Sync {
+    /// Fetch all known versions, newest first
+    fn fetch_versions(&self) -> BoxFuture<'_, Result<Vec<GoVersion>>>;
+}
+
+/// Where release archives are downloaded from
+pub trait ArtifactFetcher: Send + Sync {
+    /// Fetch the archive for `file` and write it to `dest`
+    fn fetch<'a>(&'a self, file: &'a GoFile, dest: &'a Path) -> BoxFuture<'a, Result<()>>;
+
+    /// Fetch the .tar.gz archive for `file` and unpack it into `dir`, returning
+    /// the archive's sha256. Fetchers that can, unpack it as it arrives.
+    fn fetch_unpacked<'a>(
+        &'a self,
+        file: &'a GoFile,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            fs::create_dir_all(dir)?;
+            let archive = dir.join(&file.filename);
+            self.fetch(file, &archive).await?;
+            let dir = dir.to_path_buf();
+            let checksum = tokio::task::spawn_blocking(move || {
+                let checksum = unpack_hashed(File::open(&archive)?, &dir);
+                fs::remove_file(&archive)?;
+                checksum
+            })
+            .await
+            .map_err(|e| GovmError::Archive(e.to_string()))??;
+            Ok(checksum)
+        })
+    }
+}
+
+/// go.dev, or any mirror serving the same index and file layout over HTTP
+#[derive(Debug, Clone)]
+pub struct HttpSource {
+    pub index_url: String,
+    pub download_base: String,
+    /// Send credentials from GOVM_MIRROR_TOKEN or ~/.netrc
+    pub authenticate: bool,
+}
+
+impl HttpSource {
+    /// The official go.dev download server
+    pub fn go_dev() -> Self {
+        Self {
+            index_url: GO_VERSION_LIST.to_string(),
+            download_base: GO_DOWNLOAD_BASE.to_string(),
+            authenticate: false,
+        }
+    }
+
+    /// A mirror at `download_base`, with the index at the go.dev query path
+    pub fn mirror(download_base: &str) -> Self {
+        let download_base = if download_base.ends_with('/') {
+            download_base.to_string()
+        } else {
+            format!("{}/", download_base)
+        };
+        Self {
+            index_url: format!("{}?mode=json&include=all", download_base),
+            download_base,
+            authenticate: true,
+        }
+    }
+
+    fn credentials(&self, url: &str) -> Option<auth::Credentials> {
+        if !self.authenticate {
+            return None;
+        }
+        auth::lookup(url, &self.download_base)
+    }
+}
+
+impl VersionSource for HttpSource {
+    fn fetch_versions(&self) -> BoxFuture<'_, Result<Vec<GoVersion>>> {
+        Box::pin(async move {
+            let credentials = self.credentials(&self.index_url);
+            fetch_versions_from(&self.index_url, credentials.as_ref()).await
+        })
+    }
+}
+
+impl ArtifactFetcher for HttpSource {
+    fn fetch<'a>(&'a self, file: &'a GoFile, dest: &'a Path) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let url = format!("{}{}", self.download_base, file.filename);
+            let credentials = self.credentials(&url);
+            download_file(&url, &dest.to_path_buf(), file.size, credentials.as_ref()).await
+        })
+    }
+
+    fn fetch_unpacked<'a>(
+        &'a self,
+        file: &'a GoFile,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let url = format!("{}{}", self.download_base, file.filename);
+            let credentials = self.credentials(&url);
+            download_unpacked(&url, dir, file.size, credentials.as_ref()).await
+        })
+    }
+}
+
+/// A local directory holding `index.json` (go.dev format) next to the archives
+#[derive(Debug, Clone)]
+pub struct DirSource {
+    pub dir: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl VersionSource for DirSource {
+    fn fetch_versions(&self) -> BoxFuture<'_, Result<Vec<GoVersion>>> {
+        Box::pin(async move {
+            let index = self.dir.join("index.json");
+            let content = fs::read_to_string(&index)?;
+            serde_json::from_str(&content)
+                .map_err(|e| GovmError::InvalidIndex(format!("{}: {}", index.display(), e)))
+        })
+    }
+}
+
+impl ArtifactFetcher for DirSource {
+    fn fetch<'a>(&'a self, file: &'a GoFile, dest: &'a Path) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            fs::copy(self.dir.join(&file.filename), dest)?;
+            Ok(())
+        })
+    }
+
+    fn fetch_unpacked<'a>(
+        &'a self,
+        file: &'a GoFile,
+        dir: &'a Path,
+    ) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            // Read straight from the mirror directory, no copy
+            let (archive, dir) = (self.dir.join(&file.filename), dir.to_path_buf());
+            tokio::task::spawn_blocking(move || unpack_hashed(File::open(archive)?, &dir))
+                .await
+                .map_err(|e| GovmError::Archive(e.to_string()))?
+        })
+    }
+}
+
+/// Build the version source and fetcher from the environment.
+///
+/// `GOVM_DOWNLOAD_BASE` selects a mirror URL, or a local directory when it
+/// is a path (or `file://` URL). `GOVM_VERSION_LIST` overrides the index URL.
+pub fn from_env() -> (Box<dyn VersionSource>, Box<dyn ArtifactFetcher>) {
+    from_config(&Config::from_env().mirror)
+}
+
+/// Build the version source and fetcher from the `[mirror]` settings
+pub fn from_config(mirror: &MirrorConfig) -> (Box<dyn VersionSource>, Box<dyn ArtifactFetcher>) {
+    let base = mirror.download_base.as_deref().filter(|b| !b.is_empty());
+
+    let mut http = match base {
+        Some(b) if !b.contains("://") || b.starts_with("file://") => {
+            let dir = DirSource::new(b.trim_start_matches("file://"));
+            return (Box::new(dir.clone()), Box::new(dir));
+        }
+        Some(b) => HttpSource::mirror(b),
+        None => HttpSource::go_dev(),
+    };
+    if let Some(index_url) = mirror.version_list.as_deref().filter(|u| !u.is_empty()) {
+        http.index_url = index_url.to_string();
+    }
+    (Box::new(http.clone()), Box::new(http))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_mirror_adds_trailing_slash() {
+        let source = HttpSource::mirror("https://mirror.example.com/go");
+        assert_eq!(source.download_base, "https://mirror.example.com/go/");
+        assert_eq!(
+            source.index_url,
+            "https://mirror.example.com/go/?mode=json&include=all"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dir_source_reads_index_and_copies_archive() {
+        let temp_dir = TempDir::new().unwrap();
+        let json = r#"[{"version": "go1.22.0", "stable": true, "files": []}]"#;
+        fs::write(temp_dir.path().join("index.json"), json).unwrap();
+        fs::write(temp_dir.path().join("go.tar.gz"), "archive").unwrap();
+
+        let source = DirSource::new(temp_dir.path());
+        let versions = source.fetch_versions().await.unwrap();
+        assert_eq!(versions[0].version, "go1.22.0");
+
+        let file = GoFile {
+            filename: "go.tar.gz".to_string(),
+            os: "linux".to_string(),
+            arch: "amd64".to_string(),
+            sha256: String::new(),
+            size: 7,
+            kind: "archive".to_string(),
+        };
+        let dest = temp_dir.path().join("out.tar.gz");
+        source.fetch(&file, &dest).await.unwrap();
+        assert_eq!(fs::read_to_string(dest).unwrap(), "archive");
+    }
+}
@@ -0,0 +1,132 @@
+use crate::types::GoVersion;
+use crate::version::{normalize, parse};
+
+/// Number of minor releases the Go team supports at any time
+const SUPPORTED_MINORS: usize = 2;
+
+/// Support status of an installed version relative to the remote index
+#[derive(Debug, Clone, PartialEq)]
+pub enum SupportStatus {
+    /// Latest patch of a supported minor
+    Supported,
+    /// Supported minor, but a newer patch release exists
+    Outdated { latest: String },
+    /// Minor release no longer receives security fixes
+    EndOfLife { latest: String },
+}
+
+/// Check a version against the list of remote releases.
+///
+/// Returns `None` when the version can't be judged (pre-releases, versions
+/// missing from the index, or an index without stable releases).
+pub fn check(version: &str, remote: &[GoVersion]) -> Option<SupportStatus> {
+    let (major, minor, patch, suffix) = parse(version);
+    if !suffix.is_empty() {
+        return None;
+    }
+
+    let mut stable: Vec<(u32, u32, u32)> = remote
+        .iter()
+        .filter(|v| v.stable)
+        .map(|v| parse(&normalize(&v.version)))
+        .filter(|(_, _, _, s)| s.is_empty())
+        .map(|(ma, mi, pa, _)| (ma, mi, pa))
+        .collect();
+    stable.sort_by(|a, b| b.cmp(a));
+
+    let newest = *stable.first()?;
+
+    let mut minors: Vec<(u32, u32)> = stable.iter().map(|&(ma, mi, _)| (ma, mi)).collect();
+    minors.dedup();
+
+    if minors
+        .iter()
+        .take(SUPPORTED_MINORS)
+        .any(|&m| m == (major, minor))
+    {
+        let latest_patch = stable
+            .iter()
+            .find(|&&(ma, mi, _)| (ma, mi) == (major, minor))
+            .map(|&(_, _, pa)| pa)?;
+        if latest_patch > patch {
+            return Some(SupportStatus::Outdated {
+                latest: format!("{}.{}.{}", major, minor, latest_patch),
+            });
+        }
+        return Some(SupportStatus::Supported);
+    }
+
+    if (major, minor) < (newest.0, newest.1) {
+        return Some(SupportStatus::EndOfLife {
+            latest: format!("{}.{}.{}", newest.0, newest.1, newest.2),
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(version: &str, stable: bool) -> GoVersion {
+        GoVersion {
+            version: format!("go{}", version),
+            stable,
+            files: vec![],
+        }
+    }
+
+    fn index() -> Vec<GoVersion> {
+        vec![
+            release("1.23rc1", false),
+            release("1.22.3", true),
+            release("1.22.2", true),
+            release("1.21.10", true),
+            release("1.21.9", true),
+            release("1.20.14", true),
+        ]
+    }
+
+    #[test]
+    fn test_check_latest_patch_is_supported() {
+        assert_eq!(check("1.22.3", &index()), Some(SupportStatus::Supported));
+        assert_eq!(check("1.21.10", &index()), Some(SupportStatus::Supported));
+    }
+
+    #[test]
+    fn test_check_older_patch_is_outdated() {
+        assert_eq!(
+            check("1.22.2", &index()),
+            Some(SupportStatus::Outdated {
+                latest: "1.22.3".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_old_minor_is_end_of_life() {
+        assert_eq!(
+            check("1.20.14", &index()),
+            Some(SupportStatus::EndOfLife {
+                latest: "1.22.3".to_string()
+            })
+        );
+        assert_eq!(
+            check("1.19.0", &index()),
+            Some(SupportStatus::EndOfLife {
+                latest: "1.22.3".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_check_prerelease_is_skipped() {
+        assert_eq!(check("1.23rc1", &index()), None);
+    }
+
+    #[test]
+    fn test_check_empty_index() {
+        assert_eq!(check("1.22.0", &[]), None);
+    }
+}
@@ -0,0 +1,269 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project file pinning tools, one `module/path@version` per line
+pub const TOOLS_FILE: &str = ".govm-tools";
+
+/// A tool pinned by a project
+#[derive(Debug, Clone, PartialEq)]
+pub struct PinnedTool {
+    /// Package path passed to `go install`, e.g. golang.org/x/tools/cmd/stringer
+    pub package: String,
+    /// Module version, e.g. v0.20.0
+    pub version: String,
+}
+
+impl PinnedTool {
+    /// The `package@version` argument for `go install`
+    pub fn spec(&self) -> String {
+        format!("{}@{}", self.package, self.version)
+    }
+
+    /// Name of the binary `go install` produces (the last path element, skipping a /vN suffix)
+    pub fn binary_name(&self) -> &str {
+        let mut parts = self.package.rsplit('/');
+        let last = parts.next().unwrap_or(&self.package);
+        let is_major_suffix = last.len() > 1
+            && last.starts_with('v')
+            && last[1..].chars().all(|c| c.is_ascii_digit());
+        match parts.next() {
+            Some(parent) if is_major_suffix => parent,
+            _ => last,
+        }
+    }
+
+    /// Cache directory for this tool built with `go_version`: one per (tool, version, Go version)
+    pub fn cache_dir(&self, tools_dir: &Path, go_version: &str) -> PathBuf {
+        tools_dir.join(go_version).join(self.spec())
+    }
+}
+
+/// Read a default-tools file: one `module@version` per line.
+///
+/// Blank lines and `#` comments are skipped, and a missing version means `@latest`.
+/// A missing file means no tools.
+pub fn read_default_tools(path: &Path) -> Vec<String> {
+    fs::read_to_string(path)
+        .map(|content| parse_default_tools(&content))
+        .unwrap_or_default()
+}
+
+/// Parse the content of a default-tools file
+pub fn parse_default_tools(content: &str) -> Vec<String> {
+    content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            if line.contains('@') {
+                line.to_string()
+            } else {
+                format!("{}@latest", line)
+            }
+        })
+        .collect()
+}
+
+/// Parse a .govm-tools file (same format as default-tools)
+pub fn parse_tools_file(content: &str) -> Vec<PinnedTool> {
+    parse_default_tools(content)
+        .into_iter()
+        .filter_map(|spec| {
+            let (package, version) = spec.rsplit_once('@')?;
+            Some(PinnedTool {
+                package: package.to_string(),
+                version: version.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Tools declared with go.mod `tool` directives, versioned by the matching `require`
+pub fn parse_go_mod_tools(content: &str) -> Vec<PinnedTool> {
+    let tools = directive_args(content, "tool");
+    let requires: Vec<(String, String)> = directive_args(content, "require")
+        .into_iter()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect();
+
+    tools
+        .into_iter()
+        .filter_map(|package| {
+            // The longest required module that contains the package
+            let (_, version) = requires
+                .iter()
+                .filter(|(module, _)| {
+                    package == *module || package.starts_with(&format!("{}/", module))
+                })
+                .max_by_key(|(module, _)| module.len())?;
+            Some(PinnedTool {
+                package,
+                version: version.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Arguments of a go.mod directive, from both `name arg` lines and `name ( ... )` blocks
+fn directive_args(content: &str, name: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                args.push(line.to_string());
+            }
+            continue;
+        }
+        if let Some(rest) = line.strip_prefix(name) {
+            let rest = rest.trim();
+            if !line[name.len()..].starts_with(char::is_whitespace) {
+                continue;
+            }
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                args.push(rest.to_string());
+            }
+        }
+    }
+    args
+}
+
+/// Find the tools pinned for `dir`: the nearest .govm-tools, or a go.mod with `tool` directives
+pub fn find_project_tools(dir: &Path) -> Option<(PathBuf, Vec<PinnedTool>)> {
+    for dir in dir.ancestors() {
+        let tools_file = dir.join(TOOLS_FILE);
+        if let Ok(content) = fs::read_to_string(&tools_file) {
+            return Some((tools_file, parse_tools_file(&content)));
+        }
+        let go_mod = dir.join("go.mod");
+        if let Ok(content) = fs::read_to_string(&go_mod) {
+            let tools = parse_go_mod_tools(&content);
+            if !tools.is_empty() {
+                return Some((go_mod, tools));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn tool(package: &str, version: &str) -> PinnedTool {
+        PinnedTool {
+            package: package.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_default_tools() {
+        let content = "# editor support\n\
+                       golang.org/x/tools/gopls@v0.15.0\n\
+                       \n\
+                       github.com/go-delve/delve/cmd/dlv  # debugger\n";
+        assert_eq!(
+            parse_default_tools(content),
+            [
+                "golang.org/x/tools/gopls@v0.15.0",
+                "github.com/go-delve/delve/cmd/dlv@latest"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_default_tools_missing_file() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(read_default_tools(&temp_dir.path().join("default-tools")).is_empty());
+    }
+
+    #[test]
+    fn test_binary_name() {
+        assert_eq!(
+            tool("golang.org/x/tools/cmd/stringer", "v0.20.0").binary_name(),
+            "stringer"
+        );
+        assert_eq!(
+            tool("github.com/golangci/golangci-lint/v2", "v2.1.0").binary_name(),
+            "golangci-lint"
+        );
+    }
+
+    #[test]
+    fn test_parse_go_mod_tools() {
+        let content = "module example.com/app\n\
+                       \n\
+                       go 1.24\n\
+                       \n\
+                       tool golang.org/x/tools/cmd/stringer\n\
+                       tool (\n\
+                           github.com/golangci/golangci-lint/v2/cmd/golangci-lint // lint\n\
+                           example.com/unrequired/cmd/x\n\
+                       )\n\
+                       toolchain go1.24.1\n\
+                       \n\
+                       require golang.org/x/tools v0.20.0\n\
+                       require (\n\
+                           github.com/golangci/golangci-lint/v2 v2.1.0 // indirect\n\
+                           golang.org/x/mod v0.17.0\n\
+                       )\n";
+        assert_eq!(
+            parse_go_mod_tools(content),
+            [
+                tool("golang.org/x/tools/cmd/stringer", "v0.20.0"),
+                tool(
+                    "github.com/golangci/golangci-lint/v2/cmd/golangci-lint",
+                    "v2.1.0"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_project_tools_prefers_tools_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("internal/pkg");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(
+            temp_dir.path().join("go.mod"),
+            "module m\ntool golang.org/x/tools/cmd/stringer\nrequire golang.org/x/tools v0.1.0\n",
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join(TOOLS_FILE),
+            "honnef.co/go/tools/cmd/staticcheck@2024.1.1\n",
+        )
+        .unwrap();
+
+        let (file, tools) = find_project_tools(&nested).unwrap();
+        assert_eq!(file, temp_dir.path().join(TOOLS_FILE));
+        assert_eq!(
+            tools,
+            [tool("honnef.co/go/tools/cmd/staticcheck", "2024.1.1")]
+        );
+    }
+
+    #[test]
+    fn test_cache_dir_is_keyed_by_go_version() {
+        let stringer = tool("golang.org/x/tools/cmd/stringer", "v0.20.0");
+        let tools_dir = Path::new("/home/u/.govm/tools");
+        assert_eq!(
+            stringer.cache_dir(tools_dir, "1.22.0"),
+            tools_dir.join("1.22.0/golang.org/x/tools/cmd/stringer@v0.20.0")
+        );
+        assert_ne!(
+            stringer.cache_dir(tools_dir, "1.22.0"),
+            stringer.cache_dir(tools_dir, "1.23.0")
+        );
+    }
+}
@@ -1,7 +1,7 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Represents a Go version from the official API
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct GoVersion {
     pub version: String,
     pub stable: bool,
@@ -9,12 +9,11 @@ pub struct GoVersion {
 }
 
 /// Represents a downloadable Go file/archive
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct GoFile {
     pub filename: String,
     pub os: String,
     pub arch: String,
-    #[allow(dead_code)]
     pub sha256: String,
     pub size: u64,
     pub kind: String,
@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+
+use crate::error::{GovmError, Result};
+
+/// Skip rewriting a usage stamp touched more recently than this
+const RECORD_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Record that a version was just used.
+///
+/// Called on every exec, so the stamp is only rewritten once per hour.
+pub fn record(usage_dir: &Path, version: &str) -> Result<()> {
+    let stamp = usage_dir.join(version);
+    if let Some(age) = last_used(usage_dir, version).and_then(|t| t.elapsed().ok()) {
+        if age < RECORD_INTERVAL {
+            return Ok(());
+        }
+    }
+    fs::create_dir_all(usage_dir)?;
+    fs::write(stamp, "")?;
+    Ok(())
+}
+
+/// When a version was last used, if ever
+pub fn last_used(usage_dir: &Path, version: &str) -> Option<SystemTime> {
+    fs::metadata(usage_dir.join(version))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// Forget usage of a version (on uninstall)
+pub fn clear(usage_dir: &Path, version: &str) -> Result<()> {
+    let stamp = usage_dir.join(version);
+    if stamp.exists() {
+        fs::remove_file(stamp)?;
+    }
+    Ok(())
+}
+
+/// Parse an age like "90d", "12h", "2w" or "30m"
+pub fn parse_age(s: &str) -> Result<Duration> {
+    let s = s.trim();
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let Ok(num) = num.parse::<u64>() else {
+        return Err(GovmError::InvalidAge(format!(
+            "Invalid age '{}'. Use e.g. 90d, 12h or 2w",
+            s
+        )));
+    };
+    let secs = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" | "" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => {
+            return Err(GovmError::InvalidAge(format!(
+                "Invalid age unit '{}'. Use m, h, d or w",
+                unit
+            )))
+        }
+    };
+    num.checked_mul(secs)
+        .map(Duration::from_secs)
+        .ok_or_else(|| GovmError::InvalidAge(format!("Age '{}' is too large", s)))
+}
+
+/// Format an elapsed duration for humans, e.g. "3 days ago"
+pub fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    if secs < 60 * 60 {
+        return "just now".to_string();
+    }
+    let (n, unit) = if secs < 24 * 60 * 60 {
+        (secs / (60 * 60), "hour")
+    } else {
+        (secs / (24 * 60 * 60), "day")
+    };
+    if n == 1 {
+        format!("1 {} ago", unit)
+    } else {
+        format!("{} {}s ago", n, unit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_parse_age_units() {
+        assert_eq!(parse_age("90d").unwrap(), Duration::from_secs(90 * 86400));
+        assert_eq!(parse_age("12h").unwrap(), Duration::from_secs(12 * 3600));
+        assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 86400));
+        assert_eq!(parse_age("30m").unwrap(), Duration::from_secs(30 * 60));
+        assert_eq!(parse_age("7").unwrap(), Duration::from_secs(7 * 86400));
+    }
+
+    #[test]
+    fn test_parse_age_invalid() {
+        assert!(parse_age("").is_err());
+        assert!(parse_age("d").is_err());
+        assert!(parse_age("10y").is_err());
+    }
+
+    #[test]
+    fn test_parse_age_overflow() {
+        assert!(matches!(
+            parse_age("99999999999999999w"),
+            Err(GovmError::InvalidAge(_))
+        ));
+    }
+
+    #[test]
+    fn test_format_age() {
+        assert_eq!(format_age(Duration::from_secs(10)), "just now");
+        assert_eq!(format_age(Duration::from_secs(3600)), "1 hour ago");
+        assert_eq!(format_age(Duration::from_secs(5 * 3600)), "5 hours ago");
+        assert_eq!(format_age(Duration::from_secs(86400)), "1 day ago");
+        assert_eq!(format_age(Duration::from_secs(40 * 86400)), "40 days ago");
+    }
+
+    #[test]
+    fn test_record_and_clear() {
+        let temp_dir = TempDir::new().unwrap();
+        let usage_dir = temp_dir.path().join("usage");
+
+        assert!(last_used(&usage_dir, "1.22.0").is_none());
+        record(&usage_dir, "1.22.0").unwrap();
+        assert!(last_used(&usage_dir, "1.22.0").is_some());
+
+        clear(&usage_dir, "1.22.0").unwrap();
+        assert!(last_used(&usage_dir, "1.22.0").is_none());
+    }
+}
@@ -1,9 +1,13 @@
-use anyhow::Result;
 use regex::Regex;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
+use crate::download::get_platform;
+use crate::error::Result;
+use crate::lock::{Lock, LOCK_FILE};
+use crate::projects::parse_go_mod;
+
 /// Normalize version string by removing prefixes like 'v' or 'go'
 pub fn normalize(version: &str) -> String {
     version
@@ -12,6 +16,43 @@ pub fn normalize(version: &str) -> String {
         .to_string()
 }
 
+/// Directory name for a version built for another platform, e.g. "1.22.3-linux-arm64".
+///
+/// Versions for the host platform keep the plain version name.
+pub fn with_platform(version: &str, os: &str, arch: &str) -> String {
+    if (os, arch) == get_platform() {
+        version.to_string()
+    } else {
+        format!("{}-{}-{}", version, os, arch)
+    }
+}
+
+/// The (os, arch) a version directory was installed for: the suffix added by
+/// `with_platform`, or else the host platform
+pub fn platform_of(name: &str) -> (String, String) {
+    let mut parts = name.rsplitn(3, '-');
+    if let (Some(arch), Some(os), Some(version)) = (parts.next(), parts.next(), parts.next()) {
+        if parse(version).0 > 0 {
+            return (os.to_string(), arch.to_string());
+        }
+    }
+    let (os, arch) = get_platform();
+    (os.to_string(), arch.to_string())
+}
+
+/// Parse a platform like "linux/arm64" into (os, arch)
+pub fn parse_platform(s: &str) -> std::result::Result<(String, String), String> {
+    match s.split_once('/') {
+        Some((os, arch)) if !os.is_empty() && !arch.is_empty() => {
+            Ok((os.to_string(), arch.to_string()))
+        }
+        _ => Err(format!(
+            "Invalid platform '{}'. Use OS/ARCH, e.g. linux/arm64",
+            s
+        )),
+    }
+}
+
 /// Parse version into components for comparison
 pub fn parse(v: &str) -> (u32, u32, u32, String) {
     let re = Regex::new(r"^(\d+)\.(\d+)(?:\.(\d+))?(.*)$").unwrap();
@@ -28,9 +69,15 @@ pub fn parse(v: &str) -> (u32, u32, u32, String) {
 
 /// Resolve the Go version to use based on priority:
 /// 1. GOVM_VERSION environment variable
-/// 2. .go-version file in current or parent directories
+/// 2. .go-version (or govm.lock) file in current or parent directories
 /// 3. Global version file (~/.govm/version)
 pub fn resolve(global_version_file: &PathBuf) -> Result<Option<String>> {
+    resolve_with(global_version_file, false)
+}
+
+/// Like `resolve`, but with `go_mod` set the nearest go.mod's `toolchain`
+/// (or `go`) directive is used when no .go-version file is found
+pub fn resolve_with(global_version_file: &PathBuf, go_mod: bool) -> Result<Option<String>> {
     // 1. Check environment variable
     if let Ok(version) = env::var("GOVM_VERSION") {
         let version = normalize(&version);
@@ -44,6 +91,12 @@ pub fn resolve(global_version_file: &PathBuf) -> Result<Option<String>> {
         return Ok(Some(version));
     }
 
+    if go_mod {
+        if let Some((_, version)) = find_go_mod_version()? {
+            return Ok(Some(version));
+        }
+    }
+
     // 3. Check global version
     if let Some(version) = get_global_version(global_version_file)? {
         return Ok(Some(version));
@@ -52,8 +105,25 @@ pub fn resolve(global_version_file: &PathBuf) -> Result<Option<String>> {
     Ok(None)
 }
 
+/// The nearest go.mod in the current or parent directories, and the version it requires
+pub fn find_go_mod_version() -> Result<Option<(PathBuf, String)>> {
+    for dir in env::current_dir()?.ancestors() {
+        let go_mod = dir.join("go.mod");
+        if let Ok(content) = fs::read_to_string(&go_mod) {
+            return Ok(parse_go_mod(&content).map(|version| (go_mod, version)));
+        }
+    }
+    Ok(None)
+}
+
 /// Search for .go-version file starting from current directory and walking up
 pub fn find_local_version() -> Result<Option<String>> {
+    Ok(find_local_version_file()?.map(|(_, version)| version))
+}
+
+/// Like `find_local_version`, but also returns the file that set it: a
+/// .go-version, or else a govm.lock in the same directory
+pub fn find_local_version_file() -> Result<Option<(PathBuf, String)>> {
     let mut current = env::current_dir()?;
 
     loop {
@@ -62,9 +132,14 @@ pub fn find_local_version() -> Result<Option<String>> {
             let content = fs::read_to_string(&version_file)?;
             let version = normalize(content.trim());
             if !version.is_empty() {
-                return Ok(Some(version));
+                return Ok(Some((version_file, version)));
             }
         }
+        let lock_file = current.join(LOCK_FILE);
+        if lock_file.is_file() {
+            let lock = Lock::read(&lock_file)?;
+            return Ok(Some((lock_file, lock.version)));
+        }
 
         if !current.pop() {
             break;
@@ -149,6 +224,35 @@ mod tests {
         assert!(parse("2.0.0") > parse("1.99.99"));
     }
 
+    #[test]
+    fn test_with_platform() {
+        let (os, arch) = get_platform();
+        assert_eq!(with_platform("1.22.3", os, arch), "1.22.3");
+        assert_eq!(with_platform("1.22.3", "plan9", "arm"), "1.22.3-plan9-arm");
+    }
+
+    #[test]
+    fn test_platform_of() {
+        let host = get_platform();
+        let host = (host.0.to_string(), host.1.to_string());
+        assert_eq!(
+            platform_of("1.22.3-plan9-arm"),
+            ("plan9".to_string(), "arm".to_string())
+        );
+        assert_eq!(platform_of("1.22.3"), host);
+        assert_eq!(platform_of("tip-abc1234"), host);
+    }
+
+    #[test]
+    fn test_parse_platform() {
+        assert_eq!(
+            parse_platform("linux/arm64"),
+            Ok(("linux".to_string(), "arm64".to_string()))
+        );
+        assert!(parse_platform("linux").is_err());
+        assert!(parse_platform("/arm64").is_err());
+    }
+
     #[test]
     fn test_get_global_version_exists() {
         let temp_dir = TempDir::new().unwrap();
@@ -0,0 +1,1082 @@
+//! End-to-end install tests against a local stand-in for go.dev
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::Arc;
+use std::thread;
+use tempfile::TempDir;
+
+use govm::download::get_platform;
+
+/// Build a tiny go tarball whose `go` binary is a shell script
+fn fake_go_archive(version: &str) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+
+    let mut add = |path: &str, content: String, mode: u32| {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, content.as_bytes())
+            .unwrap();
+    };
+    add("go/VERSION", format!("go{}\n", version), 0o644);
+    // `go install` records the tool next to GOROOT, fails for anything "broken",
+    // and writes a stub binary to $GOBIN when it is set
+    add(
+        "go/bin/go",
+        format!(
+            "#!/bin/sh\n\
+             if [ \"$1\" = install ]; then\n\
+               echo \"$2\" >> \"$GOROOT/installed-tools\"\n\
+               case \"$2\" in *broken*) echo \"cannot build $2\" >&2; exit 1;; esac\n\
+               if [ -n \"$GOBIN\" ]; then\n\
+                 name=$(basename \"${{2%@*}}\")\n\
+                 mkdir -p \"$GOBIN\"\n\
+                 printf '#!/bin/sh\\necho \"%s built by go{}\"\\n' \"$2\" > \"$GOBIN/$name\"\n\
+                 chmod +x \"$GOBIN/$name\"\n\
+               fi\n\
+               exit 0\n\
+             fi\n\
+             echo \"go version go{} fake\"\n",
+            version, version
+        ),
+        0o755,
+    );
+
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+/// Build a go source tarball whose make.bash writes a `go` script naming its bootstrap.
+///
+/// Release archives put the tree under `go/`; Gitiles snapshots (`root` "") don't.
+fn fake_source_archive(version: &str, root: &str) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::fast()));
+    let make_bash = r#"#!/bin/sh
+set -e
+test -n "$GOROOT_BOOTSTRAP"
+# Not installed until the build is done
+test ! -e "$HOME/.govm/versions/VERSION"
+mkdir -p ../bin
+printf '#!/bin/sh\necho "go version goVERSION built with %s"\n' "$GOROOT_BOOTSTRAP" > ../bin/go
+chmod +x ../bin/go
+"#
+    .replace("VERSION", version);
+
+    for (path, content, mode) in [
+        ("VERSION", format!("go{}\n", version), 0o644),
+        ("src/make.bash", make_bash, 0o755),
+    ] {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(mode);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, format!("{}{}", root, path), content.as_bytes())
+            .unwrap();
+    }
+
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+/// Build a go.dev-style index and the matching archives for this platform
+fn fake_release(versions: &[&str]) -> (String, HashMap<String, Vec<u8>>) {
+    let (os, arch) = get_platform();
+    fake_release_for(versions, os, arch)
+}
+
+/// Build a go.dev-style index and archives for the given platform
+fn fake_release_for(versions: &[&str], os: &str, arch: &str) -> (String, HashMap<String, Vec<u8>>) {
+    let mut files = HashMap::new();
+    let mut index = Vec::new();
+
+    for version in versions {
+        let filename = format!("go{}.{}-{}.tar.gz", version, os, arch);
+        let archive = fake_go_archive(version);
+        index.push(serde_json::json!({
+            "version": format!("go{}", version),
+            "stable": true,
+            "files": [{
+                "filename": filename,
+                "os": os,
+                "arch": arch,
+                "sha256": format!("{:x}", Sha256::digest(&archive)),
+                "size": archive.len(),
+                "kind": "archive",
+            }],
+        }));
+        files.insert(filename, archive);
+    }
+
+    (serde_json::to_string(&index).unwrap(), files)
+}
+
+/// Serve the index at `/dl/?mode=json...` and archives at `/dl/<file>`
+fn serve(index: String, files: HashMap<String, Vec<u8>>) -> String {
+    serve_with_auth(index, files, None)
+}
+
+/// Like `serve`, but answer 401 unless the request carries this Authorization header
+fn serve_with_auth(
+    index: String,
+    files: HashMap<String, Vec<u8>>,
+    authorization: Option<&'static str>,
+) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let index = Arc::new(index);
+    let files = Arc::new(files);
+
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let index = Arc::clone(&index);
+            let files = Arc::clone(&files);
+            thread::spawn(move || {
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut authorized = authorization.is_none();
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                    if let Some(expected) = authorization {
+                        if line
+                            .trim()
+                            .eq_ignore_ascii_case(&format!("authorization: {}", expected))
+                        {
+                            authorized = true;
+                        }
+                    }
+                    line.clear();
+                }
+
+                let path = request_line.split_whitespace().nth(1).unwrap_or("");
+                let name = path.trim_start_matches("/dl/");
+                let (status, body) = if !authorized {
+                    ("401 Unauthorized", Vec::new())
+                } else if name.starts_with('?') {
+                    ("200 OK", index.as_bytes().to_vec())
+                } else if let Some(data) = files.get(name) {
+                    ("200 OK", data.clone())
+                } else {
+                    ("404 Not Found", Vec::new())
+                };
+
+                let mut stream = stream;
+                let _ = write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    status,
+                    body.len()
+                );
+                let _ = stream.write_all(&body);
+            });
+        }
+    });
+
+    format!("http://{}/dl/", addr)
+}
+
+fn run_govm(args: &[&str], home: &Path, download_base: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(args)
+        .env("HOME", home)
+        .env("GOVM_DOWNLOAD_BASE", download_base)
+        .env("NO_PROXY", "127.0.0.1")
+        .env_remove("GOVM_VERSION")
+        .current_dir(home)
+        .output()
+        .expect("Failed to execute govm")
+}
+
+#[test]
+fn test_install_use_uninstall_over_http() {
+    let (index, files) = fake_release(&["1.99.1", "1.98.0"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let versions_dir = home.path().join(".govm/versions");
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(versions_dir.join("1.99.1/bin/go").exists());
+    // First install becomes the global version
+    let global = fs::read_to_string(home.path().join(".govm/version")).unwrap();
+    assert_eq!(global.trim(), "1.99.1");
+
+    let output = run_govm(&["use", "1.98.0"], home.path(), &base);
+    assert!(output.status.success());
+    assert!(versions_dir.join("1.98.0/bin/go").exists());
+
+    let output = run_govm(&["exec", "go", "version"], home.path(), &base);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("go1.98.0 fake"), "{}", stdout);
+
+    let output = run_govm(&["uninstall", "1.99.1"], home.path(), &base);
+    assert!(output.status.success());
+    assert!(!versions_dir.join("1.99.1").exists());
+    assert!(versions_dir.join("1.98.0").exists());
+}
+
+#[test]
+fn test_install_unknown_version_over_http() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(&["install", "1.50.0"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(3), "Version not found upstream");
+}
+
+#[test]
+fn test_install_missing_archive_over_http() {
+    let (index, _) = fake_release(&["1.99.1"]);
+    let base = serve(index, HashMap::new());
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(5), "HTTP 404 for the archive");
+    assert!(!home.path().join(".govm/versions/1.99.1").exists());
+}
+
+#[test]
+fn test_install_from_directory_source() {
+    let (index, files) = fake_release(&["1.99.1", "1.98.0"]);
+    let mirror = TempDir::new().unwrap();
+    fs::write(mirror.path().join("index.json"), index).unwrap();
+    for (name, data) in files {
+        fs::write(mirror.path().join(name), data).unwrap();
+    }
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(
+        &["install", "1.99.1"],
+        home.path(),
+        mirror.path().to_str().unwrap(),
+    );
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(home.path().join(".govm/versions/1.99.1/bin/go").exists());
+
+    // Parallel installs into a fresh root still make the first one global
+    let home = TempDir::new().unwrap();
+    let output = run_govm(
+        &["install", "1.99.1", "1.98.0"],
+        home.path(),
+        mirror.path().to_str().unwrap(),
+    );
+    assert!(output.status.success());
+    let global = fs::read_to_string(home.path().join(".govm/version")).unwrap();
+    assert_eq!(global.trim(), "1.99.1");
+}
+
+#[test]
+fn test_install_times_out_on_stalled_server() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/dl/", listener.local_addr().unwrap());
+    // Accept connections but never answer
+    thread::spawn(move || {
+        let _streams: Vec<_> = listener.incoming().collect();
+    });
+    let home = TempDir::new().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["install", "1.99.1"])
+        .env("HOME", home.path())
+        .env("GOVM_DOWNLOAD_BASE", &base)
+        .env("GOVM_READ_TIMEOUT", "1")
+        .env("NO_PROXY", "127.0.0.1")
+        .output()
+        .expect("Failed to execute govm");
+
+    assert_eq!(output.status.code(), Some(5), "Timeout is a network error");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Timed out"), "{}", stderr);
+}
+
+#[test]
+fn test_install_from_mirror_with_token() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve_with_auth(index, files, Some("Bearer s3cret-token"));
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(5), "Unauthorized without token");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["install", "1.99.1"])
+        .env("HOME", home.path())
+        .env("GOVM_DOWNLOAD_BASE", &base)
+        .env("GOVM_MIRROR_TOKEN", "s3cret-token")
+        .env("NO_PROXY", "127.0.0.1")
+        .output()
+        .expect("Failed to execute govm");
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let all_output = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(!all_output.contains("s3cret-token"));
+}
+
+#[test]
+fn test_install_from_mirror_with_netrc() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    // base64("alice:pa55")
+    let base = serve_with_auth(index, files, Some("Basic YWxpY2U6cGE1NQ=="));
+    let home = TempDir::new().unwrap();
+    fs::write(
+        home.path().join(".netrc"),
+        "machine 127.0.0.1 login alice password pa55\n",
+    )
+    .unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_install_for_other_platform() {
+    let (index, files) = fake_release_for(&["1.99.1"], "plan9", "arm");
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let versions_dir = home.path().join(".govm/versions");
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(4), "No binary for the host");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Available platforms: plan9/arm"),
+        "{}",
+        stderr
+    );
+
+    let output = run_govm(
+        &["install", "1.99.1", "--os", "plan9", "--arch", "arm"],
+        home.path(),
+        &base,
+    );
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(versions_dir.join("1.99.1-plan9-arm/bin/go").exists());
+    assert!(!versions_dir.join("1.99.1").exists());
+    // Cross builds never become the global version
+    assert!(!home.path().join(".govm/version").exists());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["exec", "--platform", "plan9/arm", "go", "version"])
+        .env("HOME", home.path())
+        .env("GOVM_VERSION", "1.99.1")
+        .output()
+        .expect("Failed to execute govm");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("go1.99.1 fake"), "{}", stdout);
+}
+
+#[test]
+fn test_install_refuses_zip_archive() {
+    let index = serde_json::json!([{
+        "version": "go1.99.1",
+        "stable": true,
+        "files": [{
+            "filename": "go1.99.1.windows-amd64.zip",
+            "os": "windows",
+            "arch": "amd64",
+            "sha256": "",
+            "size": 1,
+            "kind": "archive",
+        }],
+    }]);
+    // Nothing is served, so only a download attempt would fail with HTTP 404
+    let base = serve(index.to_string(), HashMap::new());
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(
+        &["install", "1.99.1", "--os", "windows", "--arch", "amd64"],
+        home.path(),
+        &base,
+    );
+    assert_eq!(output.status.code(), Some(4));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("No .tar.gz archive for windows"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_install_from_source() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let mirror = TempDir::new().unwrap();
+    for (name, data) in files {
+        fs::write(mirror.path().join(name), data).unwrap();
+    }
+    // 1.99.2 only ships a source archive
+    let source = fake_source_archive("1.99.2", "go/");
+    let mut index: Vec<serde_json::Value> = serde_json::from_str(&index).unwrap();
+    index.insert(
+        0,
+        serde_json::json!({
+            "version": "go1.99.2",
+            "stable": true,
+            "files": [{
+                "filename": "go1.99.2.src.tar.gz",
+                "os": "",
+                "arch": "",
+                "sha256": "",
+                "size": source.len(),
+                "kind": "source",
+            }],
+        }),
+    );
+    fs::write(mirror.path().join("go1.99.2.src.tar.gz"), source).unwrap();
+    fs::write(
+        mirror.path().join("index.json"),
+        serde_json::to_string(&index).unwrap(),
+    )
+    .unwrap();
+    let home = TempDir::new().unwrap();
+    let base = mirror.path().to_str().unwrap();
+
+    let output = run_govm(&["install", "--source", "1.99.2"], home.path(), base);
+    assert_eq!(output.status.code(), Some(1), "Nothing to bootstrap with");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("bootstrap"), "{}", stderr);
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), base);
+    assert!(output.status.success());
+
+    let output = run_govm(&["install", "--source", "1.99.2"], home.path(), base);
+    assert!(
+        output.status.success(),
+        "source install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["exec", "go", "version"])
+        .env("HOME", home.path())
+        .env("GOVM_VERSION", "1.99.2")
+        .output()
+        .expect("Failed to execute govm");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("go1.99.2 built with"), "{}", stdout);
+    assert!(stdout.contains("versions/1.99.1"), "{}", stdout);
+
+    // A release can't be told from a branch without the index
+    let broken = serve("not json".to_string(), HashMap::new());
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["install", "--source", "1.99.3"])
+        .env("HOME", home.path())
+        .env("GOVM_DOWNLOAD_BASE", &broken)
+        .env("GOVM_SOURCE_REPO", &broken)
+        .env("NO_PROXY", "127.0.0.1")
+        .output()
+        .expect("Failed to execute govm");
+    assert!(!output.status.success());
+    assert_ne!(output.status.code(), Some(3), "Not a missing version");
+}
+
+#[test]
+fn test_install_from_private_source_repo() {
+    let commit = "0123456789abcdef0123456789abcdef01234567";
+    let (index, mut files) = fake_release(&["1.99.1"]);
+    files.insert(
+        "repo/+/master?format=JSON".to_string(),
+        format!(")]}}'\n{{\"commit\": \"{}\"}}", commit).into_bytes(),
+    );
+    files.insert(
+        format!("repo/+archive/{}.tar.gz", commit),
+        fake_source_archive("devel", ""),
+    );
+    let base = serve_with_auth(index, files, Some("Bearer s3cret-token"));
+    let home = TempDir::new().unwrap();
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("GOVM_DOWNLOAD_BASE", &base)
+            .env("GOVM_SOURCE_REPO", format!("{}repo", base))
+            .env("GOVM_MIRROR_TOKEN", "s3cret-token")
+            .env("NO_PROXY", "127.0.0.1")
+            .output()
+            .expect("Failed to execute govm")
+    };
+
+    let output = run(&["install", "1.99.1"]);
+    assert!(output.status.success());
+    // The branch lookup and the snapshot both carry the mirror token
+    let output = run(&["install", "--source", "master"]);
+    assert!(
+        output.status.success(),
+        "source install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(home
+        .path()
+        .join(".govm/versions/tip-0123456/bin/go")
+        .exists());
+}
+
+#[test]
+fn test_install_runs_default_tools() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    fs::create_dir_all(home.path().join(".govm")).unwrap();
+    fs::write(
+        home.path().join(".govm/default-tools"),
+        "golang.org/x/tools/gopls@v0.15.0\nexample.com/broken\n",
+    )
+    .unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(
+        output.status.success(),
+        "Tool failures don't fail the install"
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("cannot build example.com/broken"),
+        "{}",
+        stdout
+    );
+    let installed =
+        fs::read_to_string(home.path().join(".govm/versions/1.99.1/installed-tools")).unwrap();
+    assert_eq!(
+        installed,
+        "golang.org/x/tools/gopls@v0.15.0\nexample.com/broken@latest\n"
+    );
+
+    let output = run_govm(&["tools", "reinstall", "1.99.1"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(1), "reinstall reports failures");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("example.com/broken@latest"), "{}", stderr);
+}
+
+#[test]
+fn test_project_tools_install_and_exec() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let project = home.path().join("project");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join(".go-version"), "1.99.1\n").unwrap();
+    fs::write(
+        project.join(".govm-tools"),
+        "example.com/cmd/mytool@v1.2.3\n",
+    )
+    .unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(output.status.success());
+
+    let govm_in_project = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home.path())
+            .env_remove("GOVM_VERSION")
+            .current_dir(&project)
+            .output()
+            .expect("Failed to execute govm")
+    };
+
+    let output = govm_in_project(&["tools", "install"]);
+    assert!(
+        output.status.success(),
+        "tools install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let cached = home
+        .path()
+        .join(".govm/tools/1.99.1/example.com/cmd/mytool@v1.2.3/mytool");
+    assert!(cached.exists());
+    assert!(home.path().join(".govm/shims/mytool").exists());
+
+    let output = govm_in_project(&["exec", "mytool"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("example.com/cmd/mytool@v1.2.3 built by go1.99.1"),
+        "{}",
+        stdout
+    );
+
+    let output = govm_in_project(&["tools", "list"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("(installed)"), "{}", stdout);
+
+    // Outside the project the tool isn't pinned
+    let output = run_govm(&["exec", "mytool"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(127));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_hooks_run_around_install_use_and_uninstall() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let (index, files) = fake_release(&["1.99.1", "1.98.0"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let hooks = home.path().join(".govm/hooks");
+    fs::create_dir_all(&hooks).unwrap();
+    let log = home.path().join("hooks.log");
+    let hook = |name: &str, script: &str| {
+        let path = hooks.join(name);
+        fs::write(&path, format!("#!/bin/sh\n{}\n", script)).unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+    };
+    let record = format!(
+        "echo \"$GOVM_HOOK $GOVM_HOOK_VERSION $GOVM_HOOK_PLATFORM $GOVM_HOOK_GOROOT\" >> {}",
+        log.display()
+    );
+    hook("post-install", &record);
+    hook("post-use", &record);
+    hook(
+        "pre-install",
+        "[ \"$GOVM_HOOK_VERSION\" != 1.98.0 ] || { echo 'not allowed' >&2; exit 1; }",
+    );
+    hook("pre-uninstall", "exit 1");
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(output.status.success());
+    let (os, arch) = get_platform();
+    let goroot = home.path().join(".govm/versions/1.99.1");
+    assert_eq!(
+        fs::read_to_string(&log).unwrap(),
+        format!(
+            "post-install 1.99.1 {os}/{arch} {g}\npost-use 1.99.1 {os}/{arch} {g}\n",
+            g = goroot.display()
+        )
+    );
+
+    // A failing pre-hook aborts the operation
+    let output = run_govm(&["install", "1.98.0"], home.path(), &base);
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pre-install"));
+    assert!(!home.path().join(".govm/versions/1.98.0").exists());
+
+    let output = run_govm(&["uninstall", "1.99.1"], home.path(), &base);
+    assert!(!output.status.success());
+    assert!(goroot.join("bin/go").exists());
+}
+
+#[test]
+fn test_verify_and_reinstall() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let goroot = home.path().join(".govm/versions/1.99.1");
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(output.status.success());
+    let output = run_govm(&["verify"], home.path(), &base);
+    assert!(output.status.success());
+
+    let go = fs::read(goroot.join("bin/go")).unwrap();
+    fs::write(goroot.join("bin/go"), "corrupted").unwrap();
+    let output = run_govm(&["verify", "1.99.1"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("modified: bin/go"), "{}", stdout);
+
+    let output = run_govm(&["reinstall", "1.99.1"], home.path(), &base);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert_eq!(fs::read(goroot.join("bin/go")).unwrap(), go);
+    let global = fs::read_to_string(home.path().join(".govm/version")).unwrap();
+    assert_eq!(global.trim(), "1.99.1");
+    let output = run_govm(&["verify"], home.path(), &base);
+    assert!(output.status.success());
+    assert!(!home.path().join(".govm/reinstall").exists());
+
+    // A reinstall killed between its two renames leaves the old tree aside;
+    // the next reinstall or install puts it back instead of deleting it
+    let aside = home.path().join(".govm/reinstall/1.99.1.old");
+    for command in ["reinstall", "install"] {
+        fs::create_dir_all(aside.parent().unwrap()).unwrap();
+        fs::rename(&goroot, &aside).unwrap();
+        let output = run_govm(&[command, "1.99.1"], home.path(), &base);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        assert_eq!(fs::read(goroot.join("bin/go")).unwrap(), go);
+        assert!(!aside.exists());
+    }
+}
+
+#[test]
+fn test_install_survives_manifest_failure() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    // Nothing can be written where the manifest goes
+    fs::create_dir_all(home.path().join(".govm/manifests/1.99.1")).unwrap();
+
+    let output = run_govm(&["install", "1.99.1"], home.path(), &base);
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("'govm verify' will skip it"), "{}", stdout);
+    assert!(home.path().join(".govm/versions/1.99.1/bin/go").exists());
+}
+
+#[test]
+fn test_lockfile_pins_checksums() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let locked = format!("{:x}", Sha256::digest(files.values().next().unwrap()));
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let project = home.path().join("app");
+    fs::create_dir_all(&project).unwrap();
+    let run_in_project = |args: &[&str], base: &str| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("GOVM_DOWNLOAD_BASE", base)
+            .env("NO_PROXY", "127.0.0.1")
+            .env_remove("GOVM_VERSION")
+            .current_dir(&project)
+            .output()
+            .expect("Failed to execute govm")
+    };
+
+    let output = run_in_project(&["lock", "1.99.1"], &base);
+    assert!(output.status.success());
+    let lock = fs::read_to_string(project.join("govm.lock")).unwrap();
+    assert!(lock.contains("version = \"1.99.1\""), "{}", lock);
+    assert!(lock.contains(&locked), "{}", lock);
+
+    // A mirror serving a different archive for the same version is refused
+    let (index, mut files) = fake_release(&["1.99.1"]);
+    let archive = files.values_mut().next().unwrap();
+    let original = format!("{:x}", Sha256::digest(&archive));
+    archive.push(0);
+    let index = index.replace(&original, &format!("{:x}", Sha256::digest(&archive)));
+    let tampered = serve(index, files);
+    let output = run_in_project(&["install", "1.99.1"], &tampered);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Checksum mismatch"));
+
+    let output = run_in_project(&["install", "1.99.1"], &base);
+    assert!(output.status.success());
+    // The lock selects the version, like .go-version
+    let output = run_in_project(&["exec", "go", "version"], &base);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("go1.99.1"));
+
+    // Without a recorded archive checksum, exec refuses to run
+    fs::remove_file(home.path().join(".govm/manifests/1.99.1.archive")).unwrap();
+    let output = run_in_project(&["exec", "go", "version"], &base);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("govm reinstall 1.99.1"));
+    let output = run_in_project(&["reinstall", "1.99.1"], &base);
+    assert!(output.status.success());
+
+    fs::write(project.join(".go-version"), "1.98.0\n").unwrap();
+    fs::create_dir_all(home.path().join(".govm/versions/1.98.0/bin")).unwrap();
+    let output = run_in_project(&["exec", "go", "version"], &base);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("pins Go 1.99.1"));
+
+    // Linked and source-built toolchains have no archive to check, and no
+    // reinstall gives them one
+    let system = home.path().join("system-go");
+    fs::create_dir_all(system.join("bin")).unwrap();
+    fs::write(system.join("bin/go"), "#!/bin/sh\n").unwrap();
+    let output = run_in_project(&["uninstall", "1.99.1"], &base);
+    assert!(output.status.success());
+    let output = run_in_project(&["link", "1.99.1", system.to_str().unwrap()], &base);
+    assert!(output.status.success());
+    fs::create_dir_all(home.path().join(".govm/versions/tip-abc1234/bin")).unwrap();
+    for (version, reason) in [
+        ("1.99.1", "is linked to"),
+        ("tip-abc1234", "was built from source"),
+    ] {
+        fs::write(project.join(".go-version"), version).unwrap();
+        let output = run_in_project(&["exec", "go", "version"], &base);
+        assert_eq!(output.status.code(), Some(1));
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        assert!(stderr.contains(reason), "{}", stderr);
+        assert!(!stderr.contains("reinstall"), "{}", stderr);
+    }
+}
+
+#[test]
+fn test_install_without_version_uses_project() {
+    let (index, files) = fake_release(&["1.99.1", "1.99.0", "1.98.0"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let project = home.path().join("app");
+    fs::create_dir_all(project.join("cmd")).unwrap();
+    fs::write(project.join("go.mod"), "module app\n\ngo 1.99\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .arg("install")
+        .env("HOME", home.path())
+        .env("GOVM_DOWNLOAD_BASE", &base)
+        .env("NO_PROXY", "127.0.0.1")
+        .current_dir(project.join("cmd"))
+        .output()
+        .expect("Failed to execute govm");
+    assert!(output.status.success());
+    // "go 1.99" means the newest 1.99.x release
+    let versions_dir = home.path().join(".govm/versions");
+    assert!(versions_dir.join("1.99.1/bin/go").exists());
+    assert!(!versions_dir.join("1.99.0").exists());
+
+    // Outside a project there is nothing to install
+    let output = run_govm(&["install"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(7));
+}
+
+#[test]
+fn test_install_all_projects() {
+    let (index, mut files) = fake_release(&["1.99.1", "1.98.0", "1.97.0"]);
+    // A mirror that swapped the 1.97.0 archive and its index entry
+    let archive = files
+        .iter_mut()
+        .find(|(name, _)| name.contains("1.97.0"))
+        .unwrap()
+        .1;
+    let original = format!("{:x}", Sha256::digest(&archive));
+    archive.push(0);
+    let index = index.replace(&original, &format!("{:x}", Sha256::digest(&archive)));
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+    let tree = home.path().join("src");
+    let (os, arch) = get_platform();
+    let lock = format!(
+        "version = \"1.97.0\"\n\n[sha256]\n\"{}/{}\" = \"{}\"\n",
+        os, arch, original
+    );
+    for (project, file, content) in [
+        ("a", ".go-version", "1.98.0\n".to_string()),
+        ("b", "go.mod", "module b\n\ngo 1.98\n".to_string()),
+        ("c", ".go-version", "1.99.1\n".to_string()),
+        ("d", ".go-version", "1.50.0\n".to_string()),
+        // `govm lock` keeps an existing .go-version next to the lock
+        ("e", ".go-version", "1.97.0\n".to_string()),
+        ("e", "govm.lock", lock),
+    ] {
+        fs::create_dir_all(tree.join(project)).unwrap();
+        fs::write(tree.join(project).join(file), content).unwrap();
+    }
+
+    let output = run_govm(
+        &["install", "--all-projects", tree.to_str().unwrap()],
+        home.path(),
+        &base,
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.contains("Failed to install Go 1.50.0, 1.97.0"),
+        "{}",
+        stderr
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Checksum mismatch"), "{}", stdout);
+    assert!(stdout.contains("Installed 2 of 4 versions"), "{}", stdout);
+    let versions_dir = home.path().join(".govm/versions");
+    assert!(versions_dir.join("1.98.0/bin/go").exists());
+    assert!(versions_dir.join("1.99.1/bin/go").exists());
+    assert!(!versions_dir.join("1.97.0").exists());
+}
+
+#[test]
+fn test_install_several_versions() {
+    let (index, files) = fake_release(&["1.99.1", "1.99.0", "1.98.0"]);
+    let base = serve(index, files);
+    let home = TempDir::new().unwrap();
+
+    let output = run_govm(&["install", "1.99", "1.98.0", "1.50.0"], home.path(), &base);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Failed to install Go 1.50.0"), "{}", stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Installed 2 of 3 versions"), "{}", stdout);
+    // Tools, hooks and manifests wait until every download is unpacked
+    let last_unpacked = stdout.rfind("Extracting Go").unwrap();
+    let first_finished = stdout.find("installed successfully").unwrap();
+    assert!(last_unpacked < first_finished, "{}", stdout);
+    // "1.99" is the newest 1.99.x
+    let versions_dir = home.path().join(".govm/versions");
+    assert!(versions_dir.join("1.99.1/bin/go").exists());
+    assert!(versions_dir.join("1.98.0/bin/go").exists());
+    assert!(!versions_dir.join("1.99.0").exists());
+
+    // One minor version resolves like several
+    let output = run_govm(&["install", "1.99"], home.path(), &base);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Go 1.99.1 is already installed"),
+        "{}",
+        stdout
+    );
+
+    let output = run_govm(&["install", "1.98.0", "1.99.1"], home.path(), &base);
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains("Go 1.99.1 is already installed"),
+        "{}",
+        stdout
+    );
+
+    let output = run_govm(
+        &["install", "1.98.0", "1.99.1", "--os", "linux"],
+        home.path(),
+        &base,
+    );
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_streaming_install() {
+    let (index, files) = fake_release(&["1.99.1", "1.98.0"]);
+    let mirror = TempDir::new().unwrap();
+    fs::write(mirror.path().join("index.json"), &index).unwrap();
+    for (name, data) in &files {
+        fs::write(mirror.path().join(name), data).unwrap();
+    }
+    let base = serve(index.clone(), files.clone());
+    let home = TempDir::new().unwrap();
+    let run_streaming = |args: &[&str], base: &str| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home.path())
+            .env("GOVM_DOWNLOAD_BASE", base)
+            .env("GOVM_STREAM_INSTALL", "1")
+            .env("NO_PROXY", "127.0.0.1")
+            .output()
+            .expect("Failed to execute govm")
+    };
+    let govm_dir = home.path().join(".govm");
+
+    let output = run_streaming(&["config", "get", "install.stream"], &base);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "true");
+
+    let output = run_streaming(&["install", "1.99.1", "1.98.0"], &base);
+    assert!(
+        output.status.success(),
+        "install failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    assert!(govm_dir.join("versions/1.99.1/bin/go").exists());
+    assert!(govm_dir.join("versions/1.98.0/bin/go").exists());
+    // No archive was written to disk
+    let archives = fs::read_dir(&govm_dir)
+        .unwrap()
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().ends_with(".tar.gz"))
+        .count();
+    assert_eq!(archives, 0);
+    let output = run_streaming(&["verify"], &base);
+    assert!(output.status.success());
+
+    // From a mirror directory the archive is read in place
+    let output = run_streaming(&["uninstall", "1.99.1"], &base);
+    assert!(output.status.success());
+    let output = run_streaming(&["install", "1.99.1"], mirror.path().to_str().unwrap());
+    assert!(output.status.success());
+    assert!(govm_dir.join("versions/1.99.1/bin/go").exists());
+
+    // An archive that doesn't match the index is unpacked, but never installed
+    let output = run_streaming(&["uninstall", "1.99.1"], &base);
+    assert!(output.status.success());
+    let mut tampered = files;
+    for archive in tampered.values_mut() {
+        archive.push(0);
+    }
+    let output = run_streaming(&["install", "1.99.1"], &serve(index, tampered));
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Checksum mismatch"));
+    assert!(!govm_dir.join("versions/1.99.1").exists());
+    assert!(!govm_dir.join("temp_extract/1.99.1").exists());
+}
+
+#[test]
+fn test_streaming_install_cut_off_mid_body() {
+    let (index, files) = fake_release(&["1.99.1"]);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}/dl/", listener.local_addr().unwrap());
+    // Promise the whole archive, but hang up halfway through it
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap_or(0) > 2 {
+                line.clear();
+            }
+            let name = request_line.split_whitespace().nth(1).unwrap_or("");
+            let (body, sent) = match files.get(name.trim_start_matches("/dl/")) {
+                Some(archive) => (archive.as_slice(), archive.len() / 2),
+                None => (index.as_bytes(), index.len()),
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            let _ = stream.write_all(&body[..sent]);
+        }
+    });
+    let home = TempDir::new().unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["install", "1.99.1"])
+        .env("HOME", home.path())
+        .env("GOVM_DOWNLOAD_BASE", &base)
+        .env("GOVM_STREAM_INSTALL", "1")
+        .env("NO_PROXY", "127.0.0.1")
+        .output()
+        .expect("Failed to execute govm");
+
+    assert_eq!(
+        output.status.code(),
+        Some(5),
+        "A cut-off download is a network error"
+    );
+    let govm_dir = home.path().join(".govm");
+    assert!(!govm_dir.join("versions/1.99.1").exists());
+    assert!(!govm_dir.join("temp_extract/1.99.1").exists());
+}
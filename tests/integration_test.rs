@@ -187,3 +187,408 @@ fn test_command_aliases_rm() {
     let output = run_govm(&["rm", "--help"]);
     assert!(output.status.success(), "rm alias should work");
 }
+
+/// Create fake installed versions under a temporary govm root
+fn fake_versions(govm_root: &std::path::Path, versions: &[&str]) {
+    for v in versions {
+        let bin = govm_root.join("versions").join(v).join("bin");
+        fs::create_dir_all(&bin).unwrap();
+        fs::write(bin.join("go"), "#!/bin/sh\n").unwrap();
+    }
+    fs::create_dir_all(govm_root.join("shims")).unwrap();
+}
+
+#[test]
+fn test_govm_prune_dry_run_keeps_versions() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.20.0", "1.21.0", "1.22.0"]);
+
+    let output = run_govm_with_root(
+        &["prune", "--keep", "1", "--dry-run"],
+        temp_dir.path().to_str().unwrap(),
+    );
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Dry run"), "Should report a dry run: {}", stdout);
+    assert!(govm_root.join("versions/1.20.0").exists());
+    assert!(govm_root.join("versions/1.21.0").exists());
+}
+
+#[test]
+fn test_govm_prune_non_tty_requires_yes() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.21.0", "1.22.0"]);
+
+    let output = run_govm_with_root(&["prune", "--keep", "1"], temp_dir.path().to_str().unwrap());
+    assert!(!output.status.success(), "Should refuse without --yes");
+    assert!(govm_root.join("versions/1.21.0").exists());
+
+    let output = run_govm_with_root(
+        &["prune", "--keep", "1", "--yes"],
+        temp_dir.path().to_str().unwrap(),
+    );
+    assert!(output.status.success());
+    assert!(!govm_root.join("versions/1.21.0").exists());
+    assert!(govm_root.join("versions/1.22.0").exists());
+}
+
+#[test]
+fn test_govm_prune_keeps_go_mod_minor_version() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.21.0", "1.22.3", "1.23.0"]);
+    let project = temp_dir.path().join("app");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join("go.mod"), "module app\n\ngo 1.22\n").unwrap();
+
+    let output = run_govm_with_root(
+        &[
+            "prune",
+            "--keep",
+            "1",
+            "--yes",
+            "--scan",
+            project.to_str().unwrap(),
+        ],
+        temp_dir.path().to_str().unwrap(),
+    );
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("used by"), "{}", stdout);
+    // "go 1.22" resolves to the installed 1.22.3
+    assert!(govm_root.join("versions/1.22.3").exists());
+    assert!(!govm_root.join("versions/1.21.0").exists());
+}
+
+#[test]
+fn test_govm_du_lists_versions() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.21.0", "1.22.0"]);
+
+    let output = run_govm_with_root(&["du"], temp_dir.path().to_str().unwrap());
+
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("1.21.0"));
+    assert!(stdout.contains("1.22.0"));
+    assert!(stdout.contains("cache"));
+    assert!(stdout.contains("total"));
+}
+
+#[test]
+fn test_govm_exec_exit_codes() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().to_str().unwrap();
+
+    let output = run_govm_with_root(&["exec", "go", "version"], home);
+    assert_eq!(output.status.code(), Some(7), "No version configured");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["exec", "go", "version"])
+        .env("HOME", home)
+        .env("GOVM_VERSION", "1.2.3")
+        .output()
+        .expect("Failed to execute govm");
+    assert_eq!(output.status.code(), Some(6), "Version not installed");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("not installed"), "{}", stderr);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_govm_link_registers_external_goroot() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().to_str().unwrap();
+    let goroot = temp_dir.path().join("system-go");
+    fs::create_dir_all(goroot.join("bin")).unwrap();
+    fs::write(goroot.join("VERSION"), "go1.21.5\n").unwrap();
+    let go = goroot.join("bin/go");
+    fs::write(&go, "#!/bin/sh\necho linked go\n").unwrap();
+    fs::set_permissions(&go, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let output = run_govm_with_root(&["link", "system", temp_dir.path().to_str().unwrap()], home);
+    assert_eq!(output.status.code(), Some(1), "Not a GOROOT");
+
+    let output = run_govm_with_root(&["link", "system", goroot.to_str().unwrap()], home);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("go1.21.5"));
+
+    let output = run_govm_with_root(&["versions"], home);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("system"));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["exec", "go"])
+        .env("HOME", home)
+        .env("GOVM_VERSION", "system")
+        .output()
+        .expect("Failed to execute govm");
+    assert!(String::from_utf8_lossy(&output.stdout).contains("linked go"));
+
+    let output = run_govm_with_root(&["uninstall", "system"], home);
+    assert!(output.status.success());
+    assert!(!temp_dir.path().join(".govm/versions/system").exists());
+    assert!(go.exists(), "The linked toolchain is left in place");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_govm_migrate_from_goenv_and_asdf() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    for goroot in [".goenv/versions/1.21.0", ".asdf/installs/golang/1.22.0/go"] {
+        fs::create_dir_all(home.join(goroot).join("bin")).unwrap();
+        fs::write(home.join(goroot).join("bin/go"), "").unwrap();
+    }
+    fs::write(home.join(".goenv/version"), "1.21.0\n").unwrap();
+    let project = home.join("src/app");
+    fs::create_dir_all(&project).unwrap();
+    fs::write(project.join(".tool-versions"), "golang 1.22.0\n").unwrap();
+
+    let migrate = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home)
+            .env_remove("GOENV_ROOT")
+            .env_remove("ASDF_DATA_DIR")
+            .output()
+            .expect("Failed to execute govm")
+    };
+
+    let output = migrate(&["migrate", "--from", "goenv"]);
+    assert!(output.status.success());
+    assert!(home.join(".govm/versions/1.21.0/bin/go").exists());
+    assert!(!home.join(".goenv/versions/1.21.0").exists(), "Moved");
+    let global = fs::read_to_string(home.join(".govm/version")).unwrap();
+    assert_eq!(global.trim(), "1.21.0");
+
+    let scan = home.join("src");
+    let output = migrate(&[
+        "migrate",
+        "--from",
+        "asdf",
+        "--link",
+        "--scan",
+        scan.to_str().unwrap(),
+    ]);
+    assert!(output.status.success());
+    let link = home.join(".govm/versions/1.22.0");
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_symlink());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("app/.tool-versions"), "{}", stdout);
+    assert!(stdout.contains("govm local 1.22.0"), "{}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_govm_isolated_gobin_gets_shims() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path();
+    let goroot = home.join(".govm/versions/1.22.0");
+    fs::create_dir_all(goroot.join("bin")).unwrap();
+    // `go install` drops a tool into $GOBIN that reports GOPATH and GOMODCACHE
+    let go = goroot.join("bin/go");
+    fs::write(
+        &go,
+        "#!/bin/sh\nmkdir -p \"$GOBIN\"\n\
+         printf '#!/bin/sh\\necho \"gopath=%s modcache=%s\"\\n' \"$GOPATH\" \"$GOMODCACHE\" > \"$GOBIN/mytool\"\n\
+         chmod +x \"$GOBIN/mytool\"\n",
+    )
+    .unwrap();
+    fs::set_permissions(&go, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let run = |args: &[&str]| {
+        Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(args)
+            .env("HOME", home)
+            .env("GOVM_VERSION", "1.22.0")
+            .env("GOVM_ISOLATE", "gopath")
+            .env_remove("GOPATH")
+            .env_remove("GOMODCACHE")
+            .output()
+            .expect("Failed to execute govm")
+    };
+
+    let output = run(&["exec", "go", "install", "example.com/mytool@latest"]);
+    assert!(output.status.success());
+    let workspace = home.join(".govm/gopath/1.22.0");
+    assert!(workspace.join("bin/mytool").exists());
+    assert!(home.join(".govm/shims/mytool").exists());
+
+    let output = run(&["exec", "mytool"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(&format!("gopath={}", workspace.display())),
+        "{}",
+        stdout
+    );
+    let shared = home.join("go/pkg/mod");
+    assert!(
+        stdout.contains(&format!("modcache={}", shared.display())),
+        "{}",
+        stdout
+    );
+
+    let output = run(&["uninstall", "1.22.0"]);
+    assert!(output.status.success());
+    assert!(!workspace.exists());
+}
+
+#[test]
+fn test_govm_config_defaults_and_overrides() {
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().to_str().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.21.0", "1.22.0"]);
+
+    let output = run_govm_with_root(&["config", "set", "prune.keep", "1"], home);
+    assert!(output.status.success());
+    let output = run_govm_with_root(&["config", "get", "prune.keep"], home);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "1");
+
+    // prune.keep replaces the built-in default of 3
+    let output = run_govm_with_root(&["prune", "--yes"], home);
+    assert!(output.status.success());
+    assert!(!govm_root.join("versions/1.21.0").exists());
+    assert!(govm_root.join("versions/1.22.0").exists());
+
+    let output = run_govm_with_root(&["config", "set", "prune.kep", "1"], home);
+    assert_eq!(output.status.code(), Some(1));
+    let output = run_govm_with_root(&["config", "set", "install.isolate", "yes"], home);
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["config", "list"])
+        .env("HOME", home)
+        .env("GOVM_ISOLATE", "gobin")
+        .output()
+        .expect("Failed to execute govm");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("prune.keep = 1"), "{}", stdout);
+    assert!(
+        stdout.contains("install.isolate = gobin (from GOVM_ISOLATE)"),
+        "{}",
+        stdout
+    );
+
+    // A value that doesn't fit is ignored, and said so
+    let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+        .args(["config", "list"])
+        .env("HOME", home)
+        .env("GOVM_ISOLATE", "everything")
+        .output()
+        .expect("Failed to execute govm");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("install.isolate = (unset)\n"), "{}", stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Ignoring GOVM_ISOLATE"), "{}", stderr);
+}
+
+#[test]
+fn test_govm_config_resolves_go_mod() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.21.0", "1.21.3", "1.22.0"]);
+    let project = temp_dir.path().join("app");
+    fs::create_dir_all(project.join("cmd")).unwrap();
+    fs::write(project.join("go.mod"), "module app\n\ngo 1.21\n").unwrap();
+
+    let which = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(["which", "go"])
+            .env("HOME", temp_dir.path())
+            .env_remove("GOVM_VERSION")
+            .current_dir(project.join("cmd"))
+            .output()
+            .expect("Failed to execute govm");
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+
+    assert!(!which().contains("1.21"), "go.mod is ignored by default");
+
+    let home = temp_dir.path().to_str().unwrap();
+    let output = run_govm_with_root(&["config", "set", "resolve.go_mod", "true"], home);
+    assert!(output.status.success());
+    // "go 1.21" selects the newest installed 1.21.x
+    let stdout = which();
+    assert!(stdout.contains("versions/1.21.3/bin/go"), "{}", stdout);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_govm_exec_warns_once_a_day_per_version() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.20.0", "1.21.0"]);
+    for v in ["1.20.0", "1.21.0"] {
+        let go = govm_root.join("versions").join(v).join("bin/go");
+        fs::set_permissions(&go, fs::Permissions::from_mode(0o755)).unwrap();
+    }
+    fs::create_dir_all(govm_root.join("cache")).unwrap();
+    fs::write(
+        govm_root.join("cache/remote-versions.json"),
+        r#"[{"version": "go1.23.0", "stable": true, "files": []},
+            {"version": "go1.22.0", "stable": true, "files": []}]"#,
+    )
+    .unwrap();
+
+    let exec = |version: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_govm"))
+            .args(["exec", "go", "version"])
+            .env("HOME", temp_dir.path())
+            .env("GOVM_VERSION", version)
+            .env_remove("GOVM_NO_SUPPORT_WARNINGS")
+            .output()
+            .expect("Failed to execute govm");
+        assert!(output.status.success());
+        String::from_utf8_lossy(&output.stderr).contains("no longer supported")
+    };
+    assert!(exec("1.20.0"));
+    assert!(exec("1.21.0"));
+    // Switching back doesn't warn again
+    assert!(!exec("1.20.0"));
+    assert!(!exec("1.21.0"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_govm_invalid_config_falls_back_to_defaults() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = TempDir::new().unwrap();
+    let home = temp_dir.path().to_str().unwrap();
+    let govm_root = temp_dir.path().join(".govm");
+    fake_versions(&govm_root, &["1.22.0"]);
+    let go = govm_root.join("versions/1.22.0/bin/go");
+    fs::write(&go, "#!/bin/sh\necho go version go1.22.0\n").unwrap();
+    fs::set_permissions(&go, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(govm_root.join("version"), "1.22.0\n").unwrap();
+    fs::write(
+        govm_root.join("config.toml"),
+        "[install]\nisolate = 3\n\n[prune]\nkeep = \"x\"\n",
+    )
+    .unwrap();
+
+    let output = run_govm_with_root(&["exec", "go", "version"], home);
+    assert!(output.status.success());
+    assert!(String::from_utf8_lossy(&output.stdout).contains("go1.22.0"));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Using the defaults"), "{}", stderr);
+
+    let output = run_govm_with_root(&["config", "set", "prune.keep", "2"], home);
+    assert!(output.status.success());
+    let output = run_govm_with_root(&["config", "unset", "install.isolate"], home);
+    assert!(output.status.success());
+    let output = run_govm_with_root(&["config", "get", "prune.keep"], home);
+    assert_eq!(String::from_utf8_lossy(&output.stdout).trim(), "2");
+    assert!(output.stderr.is_empty());
+}
@@ -0,0 +1,58 @@
+//! Tests for the govm library API
+
+use std::fs;
+use tempfile::TempDir;
+
+use govm::types::GoVersion;
+use govm::{version, GoVM};
+
+#[test]
+fn test_with_root_creates_layout() {
+    let temp_dir = TempDir::new().unwrap();
+    let root = temp_dir.path().join("govm");
+
+    let govm = GoVM::with_root(root.clone()).unwrap();
+
+    assert_eq!(govm.root_dir, root);
+    assert!(govm.versions_dir.is_dir());
+    assert!(govm.shims_dir.is_dir());
+}
+
+#[test]
+fn test_installed_versions_sorted_descending() {
+    let temp_dir = TempDir::new().unwrap();
+    let govm = GoVM::with_root(temp_dir.path().to_path_buf()).unwrap();
+    for v in ["1.9.0", "1.22.0", "1.21.5"] {
+        fs::create_dir_all(govm.versions_dir.join(v)).unwrap();
+    }
+
+    assert_eq!(
+        govm.get_installed_versions().unwrap(),
+        vec!["1.22.0", "1.21.5", "1.9.0"]
+    );
+    assert!(govm.is_version_installed("1.21.5"));
+}
+
+#[test]
+fn test_resolve_falls_back_to_global() {
+    let temp_dir = TempDir::new().unwrap();
+    let global = temp_dir.path().join("version");
+    fs::write(&global, "go1.22.0\n").unwrap();
+
+    // Away from this checkout's .go-version, and with no GOVM_VERSION override
+    std::env::remove_var("GOVM_VERSION");
+    std::env::set_current_dir(temp_dir.path()).unwrap();
+    assert_eq!(version::find_local_version().unwrap(), None);
+
+    assert_eq!(
+        version::resolve(&global).unwrap(),
+        Some("1.22.0".to_string())
+    );
+}
+
+#[test]
+fn test_remote_index_types_deserialize() {
+    let json = r#"[{"version": "go1.22.0", "stable": true, "files": []}]"#;
+    let versions: Vec<GoVersion> = serde_json::from_str(json).unwrap();
+    assert_eq!(version::normalize(&versions[0].version), "1.22.0");
+}